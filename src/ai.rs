@@ -0,0 +1,7 @@
+pub mod book;
+pub mod endgame;
+pub mod eval;
+pub mod mcts;
+pub mod random;
+pub mod search;
+pub mod tt;
@@ -0,0 +1,449 @@
+use crate::{
+    board::Board,
+    coordinates::Coords,
+    game::Game,
+    grid::Grid,
+    piece::Piece,
+    player::{Action, Player},
+    symmetry::Symmetry,
+};
+use anyhow::Result;
+use std::{collections::HashMap, fmt::Display, fs, path::Path, str::FromStr};
+use thiserror::Error;
+
+/// How many plies of each game go into a book built from game records.
+pub const DEFAULT_DEPTH: usize = 20;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BookError {
+    #[error("line {0}: expected 'moves | games wins draws', found '{1}'")]
+    InvalidLine(usize, String),
+
+    #[error("line {0}: '{1}' is not a move")]
+    InvalidMove(usize, String),
+
+    #[error("line {0}: {1} is not a legal move")]
+    IllegalMove(usize, Coords),
+
+    #[error("line {0}: the game is not over")]
+    Unfinished(usize),
+}
+
+/// Results of the games that went through a line, counted for the player
+/// who made its last move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+}
+
+impl Stats {
+    pub fn new(games: u32, wins: u32, draws: u32) -> Self {
+        Self { games, wins, draws }
+    }
+
+    /// Share of the points scored, draws counting half.
+    pub fn score(&self) -> f64 {
+        match self.games {
+            0 => 0.0,
+            games => (self.wins as f64 + self.draws as f64 / 2.0) / games as f64,
+        }
+    }
+
+    fn merge(&mut self, other: Stats) {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.draws += other.draws;
+    }
+}
+
+/// Symmetries of the board that leave the initial position unchanged, so a
+/// line and its mirror images lead to the same games.
+const SYMMETRIES: [Symmetry; 4] = [
+    Symmetry::Identity,
+    Symmetry::Rotate180,
+    Symmetry::FlipDiagonal,
+    Symmetry::FlipAntiDiagonal,
+];
+
+/// Opening lines with the results of the games that followed them. Lines are
+/// sequences of placed pieces, passes left out, played from the initial
+/// position by whichever side the first move is legal for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Book {
+    size: usize,
+    /// Stats by line, each line stored as the smallest of its mirror images.
+    lines: HashMap<Vec<Coords>, Stats>,
+}
+
+impl Book {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            lines: HashMap::new(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Reads a book for `size`x`size` boards, written one line per row such
+    /// as `E:6 F:4 | 40 22 1`. Blank lines and those starting with `#` are
+    /// skipped.
+    pub fn parse(size: usize, text: &str) -> Result<Self, BookError> {
+        let mut book = Book::new(size);
+        for (n, line) in text.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || BookError::InvalidLine(n, line.to_string());
+            let (moves, stats) = line.split_once('|').ok_or_else(invalid)?;
+            let moves = moves
+                .split_whitespace()
+                .map(Coords::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+            let stats = stats
+                .split_whitespace()
+                .map(u32::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+            let stats = match stats[..] {
+                [games, wins, draws] if wins + draws <= games => Stats::new(games, wins, draws),
+                _ => return Err(invalid()),
+            };
+            if moves.is_empty() {
+                return Err(invalid());
+            }
+            replay(&mut Board::new(size).map_err(|_| invalid())?, &moves)
+                .map_err(|at| BookError::IllegalMove(n, at))?;
+            book.add(&moves, stats);
+        }
+        Ok(book)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P, size: usize) -> Result<Self> {
+        Ok(Self::parse(size, &fs::read_to_string(path)?)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// Builds a book from finished games, one per line as a list of moves such
+    /// as `E:6 F:4 E:3`, recording their first `depth` plies.
+    pub fn from_transcripts(size: usize, text: &str, depth: usize) -> Result<Self> {
+        let mut book = Book::new(size);
+        for (n, line) in text.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let moves = line
+                .split_whitespace()
+                .filter(|m| *m != "pass")
+                .map(|m| Coords::from_str(m).map_err(|_| BookError::InvalidMove(n, m.to_string())))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut game = Game::new(size)?;
+            game.turn = first_mover(&game.board, &moves);
+            for m in &moves {
+                game.play(*m).map_err(|_| BookError::IllegalMove(n, *m))?;
+            }
+            if !game.is_over() {
+                return Err(BookError::Unfinished(n).into());
+            }
+            book.add_game(&game, depth);
+        }
+        Ok(book)
+    }
+
+    /// Adds `stats` to those of the line made of `moves`.
+    pub fn add(&mut self, moves: &[Coords], stats: Stats) {
+        self.lines
+            .entry(self.canonical(moves))
+            .or_default()
+            .merge(stats);
+    }
+
+    /// Counts the result of a finished game for its lines up to `depth` plies.
+    pub fn add_game(&mut self, game: &Game, depth: usize) {
+        let Some(result) = game.result() else {
+            return;
+        };
+        let mut line = vec![];
+        for m in game.moves().iter().filter(|m| !m.is_pass()).take(depth) {
+            line.push(m.coords().expect("passes were filtered out"));
+            let stats = match result.winner {
+                Some(winner) if winner == m.piece() => Stats::new(1, 1, 0),
+                Some(_) => Stats::new(1, 0, 0),
+                None => Stats::new(1, 0, 1),
+            };
+            self.add(&line, stats);
+        }
+    }
+
+    pub fn stats(&self, moves: &[Coords]) -> Option<Stats> {
+        self.lines.get(&self.canonical(moves)).copied()
+    }
+
+    /// Book moves for the side to move in `game`, best first.
+    pub fn moves(&self, game: &Game) -> Vec<(Coords, Stats)> {
+        if game.board.size() != self.size || game.is_over() {
+            return vec![];
+        }
+        let mut line = game
+            .moves()
+            .iter()
+            .filter_map(|m| m.coords())
+            .collect::<Vec<_>>();
+        let mut moves = game
+            .legal_moves()
+            .into_iter()
+            .filter_map(|m| {
+                line.push(m.coords);
+                let stats = self.stats(&line);
+                line.pop();
+                stats.map(|s| (m.coords, s))
+            })
+            .collect::<Vec<_>>();
+        moves.sort_by(|(_, a), (_, b)| b.score().total_cmp(&a.score()).then(b.games.cmp(&a.games)));
+        moves
+    }
+
+    /// The best book move played in at least `min_games` games.
+    pub fn choose(&self, game: &Game, min_games: u32) -> Option<Coords> {
+        self.moves(game)
+            .into_iter()
+            .find(|(_, s)| s.games >= min_games)
+            .map(|(c, _)| c)
+    }
+
+    fn canonical(&self, moves: &[Coords]) -> Vec<Coords> {
+        SYMMETRIES
+            .iter()
+            .map(|s| {
+                moves
+                    .iter()
+                    .map(|c| s.coords(self.size, *c))
+                    .collect::<Vec<_>>()
+            })
+            .min_by_key(|line| line.iter().map(|c| (c.row, c.col)).collect::<Vec<_>>())
+            .expect("there is always the identity")
+    }
+}
+
+impl Display for Book {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut lines = self.lines.iter().collect::<Vec<_>>();
+        lines.sort_by_key(|(line, _)| line.iter().map(|c| (c.row, c.col)).collect::<Vec<_>>());
+        writeln!(f, "# moves | games wins draws")?;
+        for (line, stats) in lines {
+            let moves = line.iter().map(Coords::to_string).collect::<Vec<_>>();
+            writeln!(
+                f,
+                "{} | {} {} {}",
+                moves.join(" "),
+                stats.games,
+                stats.wins,
+                stats.draws
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The side `moves` start with: the one the first move is legal for.
+fn first_mover(board: &Board, moves: &[Coords]) -> Piece {
+    match moves.first() {
+        Some(m) if Grid::flips(board, *m, Piece::Blue).is_empty() => Piece::Red,
+        Some(_) => Piece::Blue,
+        None => Piece::Red,
+    }
+}
+
+/// Plays `moves` on `board`, passing when the side to move has to, and
+/// returns the first illegal move.
+fn replay(board: &mut Board, moves: &[Coords]) -> Result<(), Coords> {
+    let mut piece = first_mover(board, moves);
+    for m in moves {
+        if !board.has_legal_move(piece) {
+            piece = !piece;
+        }
+        Grid::place(board, *m, piece).map_err(|_| *m)?;
+        piece = !piece;
+    }
+    Ok(())
+}
+
+/// Plays from a [`Book`] while the game is in it, then lets another player
+/// take over.
+pub struct BookPlayer<P: Player> {
+    book: Book,
+    fallback: P,
+    min_games: u32,
+}
+
+impl<P: Player> BookPlayer<P> {
+    pub fn new(book: Book, fallback: P) -> Self {
+        Self {
+            book,
+            fallback,
+            min_games: 1,
+        }
+    }
+
+    /// Ignores book moves played in fewer than `min_games` games.
+    pub fn with_min_games(mut self, min_games: u32) -> Self {
+        self.min_games = min_games;
+        self
+    }
+
+    pub fn book(&self) -> &Book {
+        &self.book
+    }
+}
+
+impl<P: Player> Player for BookPlayer<P> {
+    fn name(&self) -> &str {
+        self.fallback.name()
+    }
+
+    fn play(&mut self, game: &Game) -> Result<Action> {
+        match self.book.choose(game, self.min_games) {
+            Some(coords) => Ok(Action::Place(coords)),
+            None => self.fallback.play(game),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::ScriptedPlayer;
+
+    const GAMES: &str = "\
+        # Red wins the first game, Blue the other two
+        B:3 B:4 E:5 C:2 B:1 B:2 B:5 E:3 F:3
+        B:3 B:4 D:5 D:2 C:2 B:2 A:2 D:6 C:1 B:1 E:2 A:1 B:5 D:1 E:4 F:5 E:1 F:3 F:4 A:4 E:6 E:3 C:6 A:6 F:6 A:5 F:2 F:1 A:3 E:5 B:6 C:5
+        C:2 B:2 A:2 C:5 C:6 D:2 E:4 D:5 E:3 F:3 D:1 E:2 F:2 B:5 D:6 E:5 F:4 C:1 A:5 F:1 B:1 F:5 B:4 B:3 A:4 B:6 A:6 E:1 A:1 A:3 F:6 E:6
+    ";
+
+    fn game(moves: &[&str]) -> Game {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in moves {
+            game.place(m).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let text = "# moves | games wins draws\nB:3 | 3 2 1\nB:3 B:4 | 2 0 0\n";
+        let book = Book::parse(6, text).unwrap();
+
+        assert_eq!(book.len(), 2);
+        assert_eq!(book.stats(&[Coords::new(1, 2)]), Some(Stats::new(3, 2, 1)));
+        assert_eq!(book.to_string(), text);
+        assert_eq!(Book::parse(6, &book.to_string()).unwrap(), book);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Book::parse(6, "B:3 3 2 1"),
+            Err(BookError::InvalidLine(1, "B:3 3 2 1".to_string()))
+        );
+        assert_eq!(
+            Book::parse(6, "\nB:3 | 3 4 0"),
+            Err(BookError::InvalidLine(2, "B:3 | 3 4 0".to_string()))
+        );
+        assert_eq!(
+            Book::parse(6, "B:3 A:1 | 1 1 0"),
+            Err(BookError::IllegalMove(1, Coords::new(0, 0)))
+        );
+    }
+
+    #[test]
+    fn test_mirror_lines_share_stats() {
+        let mut book = Book::new(6);
+        // Red's four opening moves are mirror images of each other.
+        book.add(&[Coords::new(1, 2)], Stats::new(1, 1, 0));
+        book.add(&[Coords::new(2, 1)], Stats::new(1, 0, 0));
+        book.add(&[Coords::new(4, 3)], Stats::new(1, 0, 1));
+
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.stats(&[Coords::new(3, 4)]), Some(Stats::new(3, 1, 1)));
+    }
+
+    #[test]
+    fn test_build_from_transcripts() {
+        let book = Book::from_transcripts(6, GAMES, 4).unwrap();
+
+        // The third game opens with C:2, B:3 mirrored along the diagonal.
+        assert_eq!(book.stats(&[Coords::new(1, 2)]), Some(Stats::new(3, 1, 0)));
+        assert_eq!(
+            book.stats(&[Coords::new(1, 2), Coords::new(1, 3)]),
+            Some(Stats::new(2, 1, 0))
+        );
+        assert_eq!(book.len(), 9);
+
+        let err = Book::from_transcripts(6, "B:3 B:4", 4).unwrap_err();
+        assert_eq!(
+            err.downcast::<BookError>().unwrap(),
+            BookError::Unfinished(1)
+        );
+        let err = Book::from_transcripts(6, "B:3 A:1", 4).unwrap_err();
+        assert_eq!(
+            err.downcast::<BookError>().unwrap(),
+            BookError::IllegalMove(1, Coords::new(0, 0))
+        );
+        let err = Book::from_transcripts(6, "\nB:3 A:0", 4).unwrap_err();
+        assert_eq!(
+            err.downcast::<BookError>().unwrap(),
+            BookError::InvalidMove(2, "A:0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lookup_follows_history_through_symmetries() {
+        let book = Book::from_transcripts(6, GAMES, DEFAULT_DEPTH).unwrap();
+
+        assert_eq!(
+            book.choose(&game(&["B:3", "B:4"]), 1),
+            Some(Coords::new(4, 4))
+        );
+        assert_eq!(book.choose(&game(&["B:3", "B:4"]), 2), None);
+        assert_eq!(book.choose(&game(&["B:3"]), 1), Some(Coords::new(1, 1)));
+        // No game went C:2 D:2, but it mirrors B:3 B:4 played twice.
+        assert_eq!(book.choose(&game(&["C:2"]), 2), Some(Coords::new(3, 1)));
+    }
+
+    #[test]
+    fn test_book_player_falls_back() {
+        let book = Book::from_transcripts(6, GAMES, 2).unwrap();
+        let fallback = ScriptedPlayer::parse("script", &["E:5"]).unwrap();
+        let mut player = BookPlayer::new(book, fallback);
+        let mut game = game(&[]);
+
+        let first = player.play(&game).unwrap();
+        assert!(matches!(first, Action::Place(_)));
+        assert_eq!(player.name(), "script");
+
+        game.place("B:3").unwrap();
+        game.place("B:4").unwrap();
+        assert_eq!(
+            player.play(&game).unwrap(),
+            Action::Place(Coords::new(4, 4))
+        );
+    }
+}
@@ -0,0 +1,508 @@
+use crate::{
+    ai::tt::{Bound, Entry, TranspositionTable},
+    bitboard::{self, BitBoard},
+    board::LegalMove,
+    coordinates::Coords,
+    game::Game,
+    grid::Grid,
+    piece::Piece,
+};
+use arrayvec::ArrayVec;
+use std::time::Instant;
+
+/// Below this many empties moves are only ordered by parity, as counting the
+/// opponent's replies costs more than it saves.
+const FASTEST_FIRST_EMPTIES: usize = 7;
+
+/// Below this many empties positions aren't worth hashing into the table.
+const TABLE_EMPTIES: u32 = 6;
+
+const TABLE_CAPACITY: usize = 1 << 16;
+
+/// How many nodes are searched between two looks at the clock.
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// The four quadrants of an 8x8 board, for move ordering by parity.
+const QUADRANTS: [u64; 4] = [
+    0x0000_0000_0f0f_0f0f,
+    0x0000_0000_f0f0_f0f0,
+    0x0f0f_0f0f_0000_0000,
+    0xf0f0_f0f0_0000_0000,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Only tells whether the side to move wins (1), draws (0) or loses (-1),
+    /// which searches a much narrower window.
+    WinLossDraw,
+    /// The final disc differential under perfect play.
+    Exact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    /// The move to play, `None` when the side to move has to pass or the game
+    /// is over.
+    pub best: Option<Coords>,
+    /// Final disc differential for the side to move, empties going to the
+    /// winner, or its sign in [`Mode::WinLossDraw`].
+    pub score: i32,
+    pub nodes: u64,
+}
+
+/// Perfect play solver for the last empties of a game. 8x8 positions are
+/// solved on [`BitBoard`] masks with a transposition table, other sizes
+/// through the [`Grid`] API.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    mode: Mode,
+}
+
+impl Solver {
+    pub fn new(mode: Mode) -> Self {
+        Self { mode }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Solves the side to move of `game`.
+    pub fn solve_game(&self, game: &Game) -> Solution {
+        self.solve(&game.board, game.turn)
+    }
+
+    pub fn solve<G: Grid + Clone>(&self, grid: &G, piece: Piece) -> Solution {
+        self.solve_until(grid, piece, None)
+            .expect("a solve without deadline always completes")
+    }
+
+    /// Like [`Solver::solve`], giving up with `None` once `deadline` passes.
+    pub fn solve_until<G: Grid + Clone>(
+        &self,
+        grid: &G,
+        piece: Piece,
+        deadline: Option<Instant>,
+    ) -> Option<Solution> {
+        let (alpha, beta) = match self.mode {
+            Mode::WinLossDraw => (-1, 1),
+            Mode::Exact => (-i32::MAX, i32::MAX),
+        };
+        let mut solve = Solve::new(deadline);
+        let (score, best) = match BitBoard::from_grid(grid) {
+            Ok(bits) => solve.bits_root(bits, piece, alpha, beta),
+            Err(_) => solve.root(grid, piece, alpha, beta),
+        };
+        if solve.aborted {
+            return None;
+        }
+        let score = match self.mode {
+            Mode::WinLossDraw => score.signum(),
+            Mode::Exact => score,
+        };
+        Some(Solution {
+            best,
+            score,
+            nodes: solve.nodes,
+        })
+    }
+}
+
+struct Solve {
+    nodes: u64,
+    deadline: Option<Instant>,
+    aborted: bool,
+    table: Option<TranspositionTable>,
+}
+
+impl Solve {
+    fn new(deadline: Option<Instant>) -> Self {
+        Self {
+            nodes: 0,
+            deadline,
+            aborted: false,
+            table: None,
+        }
+    }
+
+    /// Checks the clock every [`CLOCK_INTERVAL`] nodes. Once out of time the
+    /// search unwinds and its result is thrown away.
+    fn out_of_time(&mut self) -> bool {
+        if !self.aborted && self.nodes.is_multiple_of(CLOCK_INTERVAL) {
+            self.aborted = self.deadline.is_some_and(|d| Instant::now() >= d);
+        }
+        self.aborted
+    }
+
+    fn root<G: Grid + Clone>(
+        &mut self,
+        grid: &G,
+        piece: Piece,
+        mut alpha: i32,
+        beta: i32,
+    ) -> (i32, Option<Coords>) {
+        self.nodes += 1;
+        let moves = grid.legal_moves(piece);
+        if moves.is_empty() {
+            return (self.negamax(grid, piece, alpha, beta), None);
+        }
+
+        let mut best = (-i32::MAX, None);
+        for m in ordered(grid, piece, moves) {
+            let child = play(grid, m.coords, piece);
+            let score = -self.negamax(&child, !piece, -beta, -alpha);
+            if self.aborted {
+                break;
+            }
+            if score > best.0 {
+                best = (score, Some(m.coords));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    fn negamax<G: Grid + Clone>(
+        &mut self,
+        grid: &G,
+        piece: Piece,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.out_of_time() {
+            return 0;
+        }
+        let moves = grid.legal_moves(piece);
+        if moves.is_empty() {
+            if !grid.has_legal_move(!piece) {
+                return grid.score().final_score().diff(piece) as i32;
+            }
+            return -self.negamax(grid, !piece, -beta, -alpha);
+        }
+
+        let mut best = -i32::MAX;
+        for m in ordered(grid, piece, moves) {
+            let child = play(grid, m.coords, piece);
+            let score = -self.negamax(&child, !piece, -beta, -alpha);
+            if self.aborted {
+                return 0;
+            }
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    fn bits_root(
+        &mut self,
+        bits: BitBoard,
+        piece: Piece,
+        mut alpha: i32,
+        beta: i32,
+    ) -> (i32, Option<Coords>) {
+        self.table = Some(TranspositionTable::new(TABLE_CAPACITY));
+        self.nodes += 1;
+        let moves = bits.moves(piece);
+        if moves == 0 {
+            return (self.bits_negamax(bits, piece, alpha, beta), None);
+        }
+
+        let mut best = (-i32::MAX, None);
+        for index in bits_ordered(&bits, piece, moves, None) {
+            let mut child = bits;
+            child.play(index, piece);
+            let score = -self.bits_negamax(child, !piece, -beta, -alpha);
+            if self.aborted {
+                break;
+            }
+            if score > best.0 {
+                best = (score, Some(bit_coords(index)));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    fn bits_negamax(&mut self, bits: BitBoard, piece: Piece, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.out_of_time() {
+            return 0;
+        }
+        let moves = bits.moves(piece);
+        if moves == 0 {
+            if bits.moves(!piece) == 0 {
+                return final_diff(&bits, piece);
+            }
+            return -self.bits_negamax(bits, !piece, -beta, -alpha);
+        }
+
+        let empties = bits.empty().count_ones();
+        let key = (empties >= TABLE_EMPTIES).then(|| bits.hash(piece));
+        let entry = key.and_then(|k| self.table.as_ref().and_then(|t| t.probe(k)));
+        if let Some(e) = entry {
+            let cutoff = match e.bound {
+                Bound::Exact => true,
+                Bound::Lower => e.score >= beta,
+                Bound::Upper => e.score <= alpha,
+            };
+            if cutoff {
+                return e.score;
+            }
+        }
+        let alpha_start = alpha;
+        let hint = entry.and_then(|e| e.best).map(bit_index);
+
+        let mut best = -i32::MAX;
+        let mut best_move = None;
+        for index in bits_ordered(&bits, piece, moves, hint) {
+            let mut child = bits;
+            child.play(index, piece);
+            let score = -self.bits_negamax(child, !piece, -beta, -alpha);
+            if self.aborted {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                best_move = Some(index);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if let (Some(key), Some(table)) = (key, self.table.as_mut()) {
+            let bound = match best {
+                b if b <= alpha_start => Bound::Upper,
+                b if b >= beta => Bound::Lower,
+                _ => Bound::Exact,
+            };
+            table.store(Entry {
+                key,
+                depth: empties as usize,
+                bound,
+                score: best,
+                best: best_move.map(bit_coords),
+            });
+        }
+        best
+    }
+}
+
+fn bit_index(coords: Coords) -> u32 {
+    (coords.row * bitboard::SIZE + coords.col) as u32
+}
+
+fn bit_coords(index: u32) -> Coords {
+    Coords::new(
+        index as usize / bitboard::SIZE,
+        index as usize % bitboard::SIZE,
+    )
+}
+
+/// Final disc differential for `piece`, empties going to the winner.
+fn final_diff(bits: &BitBoard, piece: Piece) -> i32 {
+    let own = bits.mask(piece).count_ones() as i32;
+    let other = bits.mask(!piece).count_ones() as i32;
+    let empty = bits.empty().count_ones() as i32;
+    match own - other {
+        d if d > 0 => d + empty,
+        d if d < 0 => d - empty,
+        _ => 0,
+    }
+}
+
+/// [`ordered`] on move masks, with the table's best move ahead of the rest.
+fn bits_ordered(bits: &BitBoard, piece: Piece, moves: u64, hint: Option<u32>) -> ArrayVec<u32, 64> {
+    let empty = bits.empty();
+    let fastest_first = empty.count_ones() as usize > FASTEST_FIRST_EMPTIES;
+
+    let mut keyed = ArrayVec::<(bool, bool, u32, u32), 64>::new();
+    let mut rest = moves;
+    while rest != 0 {
+        let index = rest.trailing_zeros();
+        rest &= rest - 1;
+        let at = 1u64 << index;
+        let even = QUADRANTS
+            .iter()
+            .find(|q| *q & at != 0)
+            .is_some_and(|q| (q & empty).count_ones().is_multiple_of(2));
+        let replies = match fastest_first {
+            true => {
+                let mut child = *bits;
+                child.play(index, piece);
+                child.moves(!piece).count_ones()
+            }
+            false => 0,
+        };
+        keyed.push((Some(index) != hint, even, replies, index));
+    }
+    keyed.sort_unstable();
+    keyed.into_iter().map(|(_, _, _, index)| index).collect()
+}
+
+fn play<G: Grid + Clone>(grid: &G, coords: Coords, piece: Piece) -> G {
+    let mut child = grid.clone();
+    child
+        .place(coords, piece)
+        .expect("legal moves can be placed");
+    child
+}
+
+/// Orders moves so cutoffs come early: moves into a quadrant with an odd
+/// number of empties first (parity), then, with enough empties left, those
+/// leaving the opponent the fewest replies (fastest first).
+fn ordered<G: Grid + Clone>(grid: &G, piece: Piece, moves: Vec<LegalMove>) -> Vec<LegalMove> {
+    let size = grid.size();
+    let half = size / 2;
+    let quadrant = |c: Coords| (c.row / half) * 2 + c.col / half;
+    let mut empties = [0usize; 4];
+    for row in 0..size {
+        for col in 0..size {
+            let coords = Coords::new(row, col);
+            if grid.piece(coords).is_none() {
+                empties[quadrant(coords)] += 1;
+            }
+        }
+    }
+    let fastest_first = empties.iter().sum::<usize>() > FASTEST_FIRST_EMPTIES;
+
+    let mut keyed = moves
+        .into_iter()
+        .map(|m| {
+            let even = empties[quadrant(m.coords)] % 2 == 0;
+            let replies = match fastest_first {
+                true => play(grid, m.coords, piece).legal_moves(!piece).len(),
+                false => 0,
+            };
+            ((even, replies), m)
+        })
+        .collect::<Vec<_>>();
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().map(|(_, m)| m).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+    use crate::board::Board;
+
+    fn brute_force<G: Grid + Clone>(grid: &G, piece: Piece) -> i32 {
+        let moves = grid.legal_moves(piece);
+        if moves.is_empty() {
+            if !grid.has_legal_move(!piece) {
+                return grid.score().final_score().diff(piece) as i32;
+            }
+            return -brute_force(grid, !piece);
+        }
+        moves
+            .iter()
+            .map(|m| -brute_force(&play(grid, m.coords, piece), !piece))
+            .max()
+            .unwrap()
+    }
+
+    /// Plays random moves until `empties` squares are left.
+    fn late_position(seed: u64, size: usize, empties: usize) -> (Board, Piece) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut board = Board::new(size).unwrap();
+        let mut piece = Piece::Red;
+        while board.score().empty() > empties {
+            let moves = Grid::legal_moves(&board, piece);
+            if let Some(m) = moves.choose(&mut rng) {
+                Grid::place(&mut board, m.coords, piece).unwrap();
+            } else if !board.has_legal_move(!piece) {
+                break;
+            }
+            piece = !piece;
+        }
+        (board, piece)
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for seed in 0..6 {
+            let (board, piece) = late_position(seed, 6, 7);
+            let expected = brute_force(&board, piece);
+
+            let exact = Solver::new(Mode::Exact).solve(&board, piece);
+            let wld = Solver::new(Mode::WinLossDraw).solve(&board, piece);
+
+            assert_eq!(exact.score, expected);
+            assert_eq!(wld.score, expected.signum());
+            assert!(wld.nodes <= exact.nodes);
+        }
+    }
+
+    #[test]
+    fn test_bitboard_solver_matches_brute_force() {
+        for seed in 0..3 {
+            let (board, piece) = late_position(seed, 8, 8);
+            let bits = BitBoard::try_from(&board).unwrap();
+
+            let exact = Solver::new(Mode::Exact).solve(&bits, piece);
+            let wld = Solver::new(Mode::WinLossDraw).solve(&board, piece);
+
+            assert_eq!(exact.score, brute_force(&bits, piece));
+            assert_eq!(wld.score, exact.score.signum());
+        }
+    }
+
+    #[test]
+    fn test_gives_up_at_the_deadline() {
+        let (board, piece) = late_position(1, 8, 20);
+        let bits = BitBoard::try_from(&board).unwrap();
+
+        let solution = Solver::new(Mode::Exact).solve_until(&bits, piece, Some(Instant::now()));
+
+        assert_eq!(solution, None);
+    }
+
+    #[test]
+    fn test_best_move_achieves_score() {
+        let (board, piece) = late_position(11, 8, 10);
+        let bits = BitBoard::try_from(&board).unwrap();
+        let solution = Solver::new(Mode::Exact).solve(&bits, piece);
+
+        match solution.best {
+            Some(best) => {
+                let child = play(&bits, best, piece);
+                let reply = Solver::new(Mode::Exact).solve(&child, !piece);
+                assert_eq!(-reply.score, solution.score);
+            }
+            None => assert!(!bits.has_legal_move(piece)),
+        }
+        assert_eq!(
+            Solver::new(Mode::Exact).solve(&board, piece).score,
+            solution.score
+        );
+    }
+
+    #[test]
+    fn test_finds_wipeout() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in ["B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3"] {
+            game.place(m).unwrap();
+        }
+
+        let solution = Solver::new(Mode::WinLossDraw).solve_game(&game);
+
+        assert_eq!(solution.best, Some(Coords::from_str("F:3").unwrap()));
+        assert_eq!(solution.score, 1);
+    }
+}
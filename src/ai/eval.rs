@@ -0,0 +1,369 @@
+use crate::{
+    coordinates::Coords,
+    grid::Grid,
+    piece::Piece,
+    walker::{Walkable, Walker},
+    Dir,
+};
+use enum_iterator::all;
+
+/// Judges a position that the search won't look past. Scores must stay well
+/// below [`WIN`](crate::ai::search::WIN) so a proven result always outranks
+/// them.
+pub trait Evaluator {
+    fn name(&self) -> &str;
+
+    /// How good `grid` is for `piece`, higher being better. Never asked about
+    /// finished games.
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32;
+}
+
+impl<E: Evaluator + ?Sized> Evaluator for Box<E> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        (**self).evaluate(grid, piece)
+    }
+}
+
+/// `mine` against `theirs` as a percentage in `-100..=100`.
+fn relative(mine: usize, theirs: usize) -> i32 {
+    match mine + theirs {
+        0 => 0,
+        total => 100 * (mine as i32 - theirs as i32) / total as i32,
+    }
+}
+
+fn squares(size: usize) -> impl Iterator<Item = Coords> {
+    (0..size * size).map(move |index| Coords::new(index / size, index % size))
+}
+
+fn corners(size: usize) -> [Coords; 4] {
+    let last = size - 1;
+    [
+        Coords::new(0, 0),
+        Coords::new(0, last),
+        Coords::new(last, 0),
+        Coords::new(last, last),
+    ]
+}
+
+/// Disc differential, the greedy evaluation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiscCount;
+
+impl Evaluator for DiscCount {
+    fn name(&self) -> &str {
+        "discs"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        grid.score().diff(piece) as i32
+    }
+}
+
+/// Legal moves available to each side.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mobility;
+
+impl Evaluator for Mobility {
+    fn name(&self) -> &str {
+        "mobility"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        relative(
+            grid.legal_moves(piece).len(),
+            grid.legal_moves(!piece).len(),
+        )
+    }
+}
+
+/// Empty squares next to an opponent disc: moves that may open up later.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PotentialMobility;
+
+impl PotentialMobility {
+    fn frontier(grid: &dyn Grid, piece: Piece) -> usize {
+        squares(grid.size())
+            .filter(|c| grid.piece(*c).is_none())
+            .filter(|c| {
+                all::<Dir>().any(|dir| {
+                    c.walker(dir)
+                        .walk_one()
+                        .is_some_and(|n| grid.piece(n) == Some(!piece))
+                })
+            })
+            .count()
+    }
+}
+
+impl Evaluator for PotentialMobility {
+    fn name(&self) -> &str {
+        "potential-mobility"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        relative(Self::frontier(grid, piece), Self::frontier(grid, !piece))
+    }
+}
+
+/// Corners held by each side.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Corners;
+
+impl Evaluator for Corners {
+    fn name(&self) -> &str {
+        "corners"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        let held = corners(grid.size()).map(|c| grid.piece(c));
+        let count = |p: Piece| held.iter().filter(|h| **h == Some(p)).count();
+        relative(count(piece), count(!piece))
+    }
+}
+
+/// Penalises discs next to an empty corner, which hand it to the opponent:
+/// twice for the diagonal X-square, once for the two C-squares along the
+/// edges.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CornerNeighbours;
+
+impl CornerNeighbours {
+    fn penalty(grid: &dyn Grid, piece: Piece) -> i32 {
+        let towards = |from: usize| if from == 0 { 1 } else { from - 1 };
+        corners(grid.size())
+            .into_iter()
+            .filter(|corner| grid.piece(*corner).is_none())
+            .map(|corner| {
+                let (row, col) = (towards(corner.row), towards(corner.col));
+                let x = Coords::new(row, col);
+                let c = [Coords::new(corner.row, col), Coords::new(row, corner.col)];
+                let owned = |c: Coords| (grid.piece(c) == Some(piece)) as i32;
+                2 * owned(x) + c.into_iter().map(owned).sum::<i32>()
+            })
+            .sum()
+    }
+}
+
+impl Evaluator for CornerNeighbours {
+    fn name(&self) -> &str {
+        "x-c-squares"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        Self::penalty(grid, !piece) - Self::penalty(grid, piece)
+    }
+}
+
+/// Discs that can never be flipped again, see [`crate::stability`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stability;
+
+impl Evaluator for Stability {
+    fn name(&self) -> &str {
+        "stability"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        relative(grid.stable(piece).len(), grid.stable(!piece).len())
+    }
+}
+
+/// Sums a weight per square, positive for owned discs and negative for the
+/// opponent's. The classic 8x8 table is stretched to any size by ranking a
+/// square by its distance to the nearest edges.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Positional;
+
+impl Positional {
+    pub fn weight(size: usize, coords: Coords) -> i32 {
+        let row = coords.row.min(size - 1 - coords.row);
+        let col = coords.col.min(size - 1 - coords.col);
+        match (row.min(col), row.max(col)) {
+            (0, 0) => 100,
+            (0, 1) => -20,
+            (1, 1) => -50,
+            (0, 2) => 10,
+            (0, _) => 5,
+            (1, _) => -2,
+            _ => -1,
+        }
+    }
+}
+
+impl Evaluator for Positional {
+    fn name(&self) -> &str {
+        "positional"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        let size = grid.size();
+        squares(size)
+            .map(|c| match grid.piece(c) {
+                Some(p) if p == piece => Self::weight(size, c),
+                Some(_) => -Self::weight(size, c),
+                None => 0,
+            })
+            .sum()
+    }
+}
+
+/// A weighted sum of other evaluators.
+#[derive(Default)]
+pub struct Weighted {
+    terms: Vec<(i32, Box<dyn Evaluator>)>,
+}
+
+impl Weighted {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A blend of every built-in heuristic favouring corners and mobility.
+    pub fn standard() -> Self {
+        Self::new()
+            .with(1, DiscCount)
+            .with(5, Mobility)
+            .with(3, PotentialMobility)
+            .with(30, Corners)
+            .with(20, CornerNeighbours)
+            .with(10, Stability)
+            .with(1, Positional)
+    }
+
+    pub fn with<E: Evaluator + 'static>(mut self, weight: i32, evaluator: E) -> Self {
+        self.terms.push((weight, Box::new(evaluator)));
+        self
+    }
+}
+
+impl Evaluator for Weighted {
+    fn name(&self) -> &str {
+        "weighted"
+    }
+
+    fn evaluate(&self, grid: &dyn Grid, piece: Piece) -> i32 {
+        self.terms
+            .iter()
+            .map(|(weight, evaluator)| weight * evaluator.evaluate(grid, piece))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bitboard::BitBoard, board::Board};
+
+    fn board(size: usize, blue: &[(usize, usize)], red: &[(usize, usize)]) -> Board {
+        let mut cells = vec![None; size * size];
+        for (row, col) in blue {
+            cells[row * size + col] = Some(Piece::Blue);
+        }
+        for (row, col) in red {
+            cells[row * size + col] = Some(Piece::Red);
+        }
+        Board::from_cells(size, &cells).unwrap()
+    }
+
+    fn evaluators() -> Vec<Box<dyn Evaluator>> {
+        vec![
+            Box::new(DiscCount),
+            Box::new(Mobility),
+            Box::new(PotentialMobility),
+            Box::new(Corners),
+            Box::new(CornerNeighbours),
+            Box::new(Stability),
+            Box::new(Positional),
+            Box::new(Weighted::standard()),
+        ]
+    }
+
+    #[test]
+    fn test_initial_position_is_balanced() {
+        for size in [6, 8, 10] {
+            let board = Board::new(size).unwrap();
+            for evaluator in evaluators() {
+                assert_eq!(
+                    evaluator.evaluate(&board, Piece::Blue),
+                    0,
+                    "{} on {}x{}",
+                    evaluator.name(),
+                    size,
+                    size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_evaluations_are_symmetric() {
+        let board = board(
+            8,
+            &[(0, 0), (0, 1), (1, 1), (3, 3)],
+            &[(2, 2), (3, 4), (4, 3)],
+        );
+        let bits = BitBoard::try_from(&board).unwrap();
+
+        for evaluator in evaluators() {
+            let blue = evaluator.evaluate(&board, Piece::Blue);
+            assert_eq!(blue, -evaluator.evaluate(&board, Piece::Red));
+            assert_eq!(blue, evaluator.evaluate(&bits, Piece::Blue));
+        }
+    }
+
+    #[test]
+    fn test_corners_and_neighbours() {
+        let board = board(6, &[(0, 0), (1, 4)], &[(0, 4), (5, 0), (5, 5)]);
+
+        assert_eq!(Corners.evaluate(&board, Piece::Blue), -33);
+        // Red's C-square at A:5 and Blue's X-square at B:5 both touch the
+        // empty corner A:6.
+        assert_eq!(CornerNeighbours.evaluate(&board, Piece::Blue), -1);
+    }
+
+    #[test]
+    fn test_stability() {
+        let board = board(
+            6,
+            &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)],
+            &[(0, 3), (5, 5)],
+        );
+
+        // Five stable Blue discs against Red's corner.
+        assert_eq!(Stability.evaluate(&board, Piece::Blue), 66);
+    }
+
+    #[test]
+    fn test_positional_weights_scale() {
+        let table = |size: usize| {
+            (0..size)
+                .map(|col| Positional::weight(size, Coords::new(0, col)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(table(8), vec![100, -20, 10, 5, 5, 10, -20, 100]);
+        assert_eq!(table(6), vec![100, -20, 10, 10, -20, 100]);
+        assert_eq!(Positional::weight(8, Coords::new(1, 1)), -50);
+        assert_eq!(Positional::weight(12, Coords::new(10, 10)), -50);
+        assert_eq!(Positional::weight(12, Coords::new(5, 6)), -1);
+    }
+
+    #[test]
+    fn test_mobility() {
+        let board = Board::new(6).unwrap();
+        let mut after = board.clone();
+        Grid::place(&mut after, Coords::new(1, 2), Piece::Red).unwrap();
+
+        assert_eq!(Mobility.evaluate(&board, Piece::Red), 0);
+        // Three replies each, but Red's four discs border many more empty
+        // squares than Blue's last one.
+        assert_eq!(Mobility.evaluate(&after, Piece::Blue), 0);
+        assert_eq!(PotentialMobility::frontier(&after, Piece::Red), 5);
+        assert!(PotentialMobility.evaluate(&after, Piece::Blue) > 0);
+    }
+}
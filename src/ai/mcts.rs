@@ -0,0 +1,430 @@
+use crate::{
+    ai::eval::Positional,
+    bitboard::BitBoard,
+    coordinates::Coords,
+    game::Game,
+    grid::Grid,
+    piece::Piece,
+    player::{Action, Player},
+};
+use anyhow::Result;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The usual UCT exploration constant, √2.
+pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// How far below the root a tree kept from the previous search is looked
+/// into for the new position: our move, a pass and the opponent's reply.
+const REUSE_DEPTH: usize = 3;
+
+/// When a search stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(u64),
+    Time(Duration),
+}
+
+/// How moves are picked when playing a game out to its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playout {
+    /// Uniformly among the legal moves.
+    Random,
+    /// Takes corners when it can and otherwise avoids the squares next to
+    /// them, picking randomly among the rest.
+    Guided,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsReport {
+    /// The move to play, `None` when the side to move has to pass or the game
+    /// is over.
+    pub best: Option<Action>,
+    /// Share of the playouts through `best` won by the side to move, draws
+    /// counting half.
+    pub win_rate: f64,
+    /// Iterations run by this search.
+    pub iterations: u64,
+    /// Playouts behind the root, including those kept from earlier searches.
+    pub visits: u32,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// The move leading here, `None` for a pass or the root.
+    coords: Option<Coords>,
+    /// Who played that move; wins are counted for them.
+    mover: Piece,
+    /// Hash of the position reached, with the opponent of `mover` to play.
+    hash: u64,
+    children: Vec<usize>,
+    /// Moves not expanded yet, filled on the first visit.
+    untried: Option<Vec<Option<Coords>>>,
+    visits: u32,
+    wins: f64,
+}
+
+impl Node {
+    fn new(coords: Option<Coords>, mover: Piece, hash: u64) -> Self {
+        Self {
+            coords,
+            mover,
+            hash,
+            children: vec![],
+            untried: None,
+            visits: 0,
+            wins: 0.0,
+        }
+    }
+
+    fn expanded(&self) -> bool {
+        self.untried.as_ref().is_some_and(|u| u.is_empty())
+    }
+}
+
+/// Monte Carlo tree search guided by UCT. The tree of a search is kept and
+/// its subtree for the next position is reused, so the opponent's replies
+/// already explored aren't thrown away.
+pub struct Mcts<R: Rng = StdRng> {
+    budget: Budget,
+    exploration: f64,
+    playout: Playout,
+    rng: R,
+    /// Nodes of the kept tree, its root first.
+    tree: Vec<Node>,
+}
+
+impl Mcts<StdRng> {
+    /// A reproducible player: the same seed plays the same moves on an
+    /// iteration budget.
+    pub fn seeded(budget: Budget, seed: u64) -> Self {
+        Self::new(budget, StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> Mcts<R> {
+    pub fn new(budget: Budget, rng: R) -> Self {
+        Self {
+            budget,
+            exploration: DEFAULT_EXPLORATION,
+            playout: Playout::Random,
+            rng,
+            tree: vec![],
+        }
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    pub fn with_playout(mut self, playout: Playout) -> Self {
+        self.playout = playout;
+        self
+    }
+
+    pub fn budget(&self) -> Budget {
+        self.budget
+    }
+
+    pub fn exploration(&self) -> f64 {
+        self.exploration
+    }
+
+    pub fn playout(&self) -> Playout {
+        self.playout
+    }
+
+    /// Nodes in the kept tree.
+    pub fn tree_size(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Searches the side to move of `game`, on a [`BitBoard`] when the board
+    /// is 8x8.
+    pub fn search_game(&mut self, game: &Game) -> MctsReport {
+        match BitBoard::try_from(&game.board) {
+            Ok(bits) => self.search(&bits, game.turn),
+            Err(_) => self.search(&game.board, game.turn),
+        }
+    }
+
+    pub fn search<G: Grid + Clone>(&mut self, grid: &G, piece: Piece) -> MctsReport {
+        let start = Instant::now();
+        self.reroot(grid.hash(piece), piece);
+
+        let mut iterations = 0;
+        loop {
+            self.iterate(grid, piece);
+            iterations += 1;
+            let done = match self.budget {
+                Budget::Iterations(n) => iterations >= n,
+                Budget::Time(limit) => start.elapsed() >= limit,
+            };
+            if done {
+                break;
+            }
+        }
+
+        let root = &self.tree[0];
+        let best = root
+            .children
+            .iter()
+            .map(|&i| &self.tree[i])
+            .max_by_key(|child| child.visits);
+        MctsReport {
+            best: best.and_then(|child| child.coords).map(Action::Place),
+            win_rate: best.map_or(0.0, |child| child.wins / child.visits as f64),
+            iterations,
+            visits: root.visits,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    /// Makes the node for `hash` the root, keeping only its subtree, or
+    /// starts a new tree when the kept one doesn't reach it.
+    fn reroot(&mut self, hash: u64, piece: Piece) {
+        let mut queue = match self.tree.is_empty() {
+            true => VecDeque::new(),
+            false => VecDeque::from([(0, 0)]),
+        };
+        let mut found = None;
+        while let Some((i, depth)) = queue.pop_front() {
+            if self.tree[i].hash == hash {
+                found = Some(i);
+                break;
+            }
+            if depth < REUSE_DEPTH {
+                queue.extend(self.tree[i].children.iter().map(|&c| (c, depth + 1)));
+            }
+        }
+
+        let Some(found) = found else {
+            self.tree = vec![Node::new(None, !piece, hash)];
+            return;
+        };
+        let mut old = std::mem::take(&mut self.tree);
+        let mut pending = VecDeque::from([found]);
+        while let Some(i) = pending.pop_front() {
+            let mut node = std::mem::replace(&mut old[i], Node::new(None, piece, 0));
+            let first = self.tree.len() + pending.len() + 1;
+            pending.extend(node.children.iter().copied());
+            node.children = (first..first + node.children.len()).collect();
+            self.tree.push(node);
+        }
+    }
+
+    /// Selects a leaf, expands one move, plays the game out and records the
+    /// result along the path.
+    fn iterate<G: Grid + Clone>(&mut self, grid: &G, piece: Piece) {
+        let mut grid = grid.clone();
+        let mut piece = piece;
+        let mut path = vec![0];
+        let mut current = 0;
+
+        while self.tree[current].expanded() && !self.tree[current].children.is_empty() {
+            current = self.select(current);
+            if let Some(coords) = self.tree[current].coords {
+                grid.place(coords, piece).expect("tree moves are legal");
+            }
+            piece = !piece;
+            path.push(current);
+        }
+
+        let node = &mut self.tree[current];
+        let untried = node.untried.get_or_insert_with(|| {
+            let moves = grid.legal_moves(piece);
+            match moves.is_empty() {
+                true if grid.has_legal_move(!piece) => vec![None],
+                _ => moves.into_iter().map(|m| Some(m.coords)).collect(),
+            }
+        });
+        if !untried.is_empty() {
+            let coords = untried.swap_remove(self.rng.gen_range(0..untried.len()));
+            if let Some(coords) = coords {
+                grid.place(coords, piece)
+                    .expect("legal moves can be placed");
+            }
+            let child = Node::new(coords, piece, grid.hash(!piece));
+            self.tree.push(child);
+            let index = self.tree.len() - 1;
+            self.tree[current].children.push(index);
+            piece = !piece;
+            path.push(index);
+        }
+
+        let winner = self.play_out(&mut grid, piece);
+        for i in path {
+            let node = &mut self.tree[i];
+            node.visits += 1;
+            node.wins += match winner {
+                Some(w) if w == node.mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+        }
+    }
+
+    fn select(&self, parent: usize) -> usize {
+        let log_visits = (self.tree[parent].visits as f64).ln();
+        let uct = |i: usize| {
+            let node = &self.tree[i];
+            let visits = node.visits as f64;
+            node.wins / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+        self.tree[parent]
+            .children
+            .iter()
+            .copied()
+            .max_by(|a, b| uct(*a).total_cmp(&uct(*b)))
+            .expect("selected nodes have children")
+    }
+
+    /// Plays until the game ends and returns the winner, `None` on a draw.
+    fn play_out<G: Grid>(&mut self, grid: &mut G, mut piece: Piece) -> Option<Piece> {
+        loop {
+            let moves = grid.legal_moves(piece);
+            if moves.is_empty() && !grid.has_legal_move(!piece) {
+                return grid.score().leader();
+            }
+            let coords = moves.iter().map(|m| m.coords).collect::<Vec<_>>();
+            if let Some(coords) = choose(self.playout, grid.size(), &coords, &mut self.rng) {
+                grid.place(coords, piece)
+                    .expect("legal moves can be placed");
+            }
+            piece = !piece;
+        }
+    }
+}
+
+fn choose<R: Rng>(playout: Playout, size: usize, moves: &[Coords], rng: &mut R) -> Option<Coords> {
+    match playout {
+        Playout::Random => moves.choose(rng).copied(),
+        Playout::Guided => {
+            let weight = |c: &Coords| Positional::weight(size, *c);
+            let corner = moves.iter().find(|c| weight(c) == 100);
+            let safe = moves
+                .iter()
+                .copied()
+                .filter(|c| weight(c) > -20)
+                .collect::<Vec<_>>();
+            corner
+                .copied()
+                .or_else(|| safe.choose(rng).copied())
+                .or_else(|| moves.choose(rng).copied())
+        }
+    }
+}
+
+impl<R: Rng> Player for Mcts<R> {
+    fn name(&self) -> &str {
+        "mcts"
+    }
+
+    fn play(&mut self, game: &Game) -> Result<Action> {
+        Ok(self.search_game(game).best.unwrap_or(Action::Pass))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{ai::random::RandomPlayer, board::Board, player::Runner};
+
+    #[test]
+    fn test_beats_random_player() {
+        let mut wins = 0;
+        for seed in 0..4 {
+            let mut game = Game::new(8).unwrap();
+            let mcts = Mcts::seeded(Budget::Iterations(40), seed);
+            let result = Runner::new(mcts, RandomPlayer::seeded(seed))
+                .run(&mut game)
+                .unwrap();
+            if result.winner == Some(Piece::Blue) {
+                wins += 1;
+            }
+        }
+
+        assert!(wins >= 3, "won {} of 4", wins);
+    }
+
+    #[test]
+    fn test_finds_winning_move() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in ["B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3"] {
+            game.place(m).unwrap();
+        }
+
+        let report = Mcts::seeded(Budget::Iterations(2000), 1).search_game(&game);
+
+        assert_eq!(
+            report.best,
+            Some(Action::Place(Coords::from_str("F:3").unwrap()))
+        );
+        assert_eq!(report.win_rate, 1.0);
+    }
+
+    #[test]
+    fn test_reuses_tree_between_moves() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Red;
+        let mut mcts = Mcts::seeded(Budget::Iterations(300), 5);
+
+        let first = mcts.search_game(&game);
+        let Some(Action::Place(coords)) = first.best else {
+            panic!("Red has moves");
+        };
+        game.play(coords).unwrap();
+        let reply = game.legal_moves()[0].coords;
+        game.play(reply).unwrap();
+        let second = mcts.search_game(&game);
+
+        assert_eq!(first.visits, 300);
+        assert_eq!(second.iterations, 300);
+        assert!(second.visits > 300);
+        assert_eq!(mcts.tree_size() as u32, second.visits);
+    }
+
+    #[test]
+    fn test_time_budget_on_large_board() {
+        let board = Board::new(16).unwrap();
+        let limit = Duration::from_millis(100);
+        let mut mcts = Mcts::seeded(Budget::Time(limit), 2).with_playout(Playout::Guided);
+
+        let report = mcts.search(&board, Piece::Blue);
+
+        assert!(report.best.is_some());
+        assert!(report.iterations >= 1);
+
+        let mut mcts = Mcts::seeded(Budget::Time(Duration::ZERO), 2);
+        let report = mcts.search(&board, Piece::Blue);
+
+        assert!(report.best.is_some());
+        assert_eq!(report.iterations, 1);
+    }
+
+    #[test]
+    fn test_guided_playout_prefers_corners() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let moves = [Coords::new(1, 1), Coords::new(3, 4), Coords::new(7, 0)];
+
+        for _ in 0..10 {
+            assert_eq!(
+                choose(Playout::Guided, 8, &moves, &mut rng),
+                Some(Coords::new(7, 0))
+            );
+            assert_eq!(
+                choose(Playout::Guided, 8, &moves[..2], &mut rng),
+                Some(Coords::new(3, 4))
+            );
+        }
+        assert_eq!(choose(Playout::Guided, 8, &[], &mut rng), None);
+    }
+}
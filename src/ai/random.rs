@@ -0,0 +1,80 @@
+use crate::{
+    coordinates::Coords,
+    game::Game,
+    player::{Action, Player},
+};
+use anyhow::Result;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Baseline opponent picking uniformly among the legal moves of the side to
+/// move.
+pub struct RandomPlayer<R: Rng = StdRng> {
+    rng: R,
+}
+
+impl RandomPlayer<StdRng> {
+    /// A reproducible player: the same seed always picks the same moves.
+    pub fn seeded(seed: u64) -> Self {
+        Self::new(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> RandomPlayer<R> {
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+
+    /// A legal move for `game.turn`, `None` once the game is over.
+    pub fn choose(&mut self, game: &Game) -> Option<Coords> {
+        if game.is_over() {
+            return None;
+        }
+        game.legal_moves().choose(&mut self.rng).map(|m| m.coords)
+    }
+}
+
+impl<R: Rng> Player for RandomPlayer<R> {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn play(&mut self, game: &Game) -> Result<Action> {
+        Ok(self.choose(game).map_or(Action::Pass, Action::Place))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Piece;
+
+    fn play_out(seed: u64) -> Game {
+        let mut player = RandomPlayer::seeded(seed);
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Red;
+        while let Some(coords) = player.choose(&game) {
+            game.play(coords).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_choose_is_legal() {
+        let mut player = RandomPlayer::seeded(1);
+        let game = Game::new(6).unwrap();
+
+        let coords = player.choose(&game).unwrap();
+
+        assert!(game.legal_moves().iter().any(|m| m.coords == coords));
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let a = play_out(42);
+        let b = play_out(42);
+
+        assert!(a.is_over());
+        assert_eq!(a.moves(), b.moves());
+        assert_eq!(a.result(), b.result());
+    }
+}
@@ -0,0 +1,667 @@
+use crate::{
+    ai::{
+        endgame::{Mode, Solver},
+        eval::{DiscCount, Evaluator},
+        tt::{Bound, Entry, TranspositionTable},
+    },
+    bitboard::BitBoard,
+    game::Game,
+    grid::Grid,
+    piece::Piece,
+    player::{Action, Player},
+};
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// Score of a won game before adding the final disc differential, so any win
+/// outranks any evaluation.
+pub const WIN: i32 = 1_000_000;
+
+const INFINITY: i32 = i32::MAX;
+
+/// How many nodes are searched between two looks at the clock.
+const CLOCK_INTERVAL: u64 = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The move to play: [`Action::Pass`] when the side to move has none but
+    /// the game goes on, `None` once the game is over.
+    pub best: Option<Action>,
+    /// Score from the point of view of the side to move.
+    pub score: i32,
+    /// Expected line of play, starting with `best`.
+    pub pv: Vec<Action>,
+    pub nodes: u64,
+}
+
+/// Outcome of an iterative deepening search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchReport {
+    /// Result of the deepest completed iteration.
+    pub result: SearchResult,
+    /// Depth of that iteration.
+    pub depth: usize,
+    /// Nodes searched over every iteration, including an aborted last one.
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+impl SearchReport {
+    pub fn nodes_per_second(&self) -> u64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => (self.nodes as f64 / secs) as u64,
+            _ => self.nodes,
+        }
+    }
+}
+
+/// Negamax search with alpha-beta pruning, either to a fixed depth or
+/// deepening iteratively within a time budget.
+/// Results are kept in a transposition table shared by every search this
+/// engine runs. Close enough to the end, the game can be solved exactly
+/// instead. Leaves are scored by `E`, the disc count unless told otherwise.
+#[derive(Debug, Clone)]
+pub struct AlphaBeta<E: Evaluator = DiscCount> {
+    depth: usize,
+    time_limit: Option<Duration>,
+    table: TranspositionTable,
+    endgame: usize,
+    evaluator: E,
+}
+
+impl AlphaBeta {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            time_limit: None,
+            table: TranspositionTable::default(),
+            endgame: 0,
+            evaluator: DiscCount,
+        }
+    }
+
+    /// Deepens as far as `time_limit` allows.
+    pub fn timed(time_limit: Duration) -> Self {
+        Self::new(usize::MAX).with_time_limit(time_limit)
+    }
+}
+
+impl<E: Evaluator> AlphaBeta<E> {
+    /// Scores leaves with `evaluator` instead.
+    pub fn with_evaluator<F: Evaluator>(self, evaluator: F) -> AlphaBeta<F> {
+        AlphaBeta {
+            depth: self.depth,
+            time_limit: self.time_limit,
+            table: self.table,
+            endgame: self.endgame,
+            evaluator,
+        }
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Replaces the transposition table by one of `capacity` slots, zero
+    /// disabling it.
+    pub fn with_table_capacity(mut self, capacity: usize) -> Self {
+        self.table = TranspositionTable::new(capacity);
+        self
+    }
+
+    /// Hands positions with at most `empties` empty squares to the endgame
+    /// [`Solver`], zero never doing so.
+    pub fn with_endgame(mut self, empties: usize) -> Self {
+        self.endgame = empties;
+        self
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn endgame(&self) -> usize {
+        self.endgame
+    }
+
+    pub fn table(&self) -> &TranspositionTable {
+        &self.table
+    }
+
+    pub fn evaluator(&self) -> &E {
+        &self.evaluator
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Searches the side to move of `game`, on a [`BitBoard`] when the board
+    /// is 8x8.
+    pub fn search_game(&mut self, game: &Game) -> SearchResult {
+        match BitBoard::try_from(&game.board) {
+            Ok(bits) => self.search(&bits, game.turn),
+            Err(_) => self.search(&game.board, game.turn),
+        }
+    }
+
+    /// A single search to `depth`, ignoring the time limit.
+    pub fn search<G: Grid + Clone>(&mut self, grid: &G, piece: Piece) -> SearchResult {
+        if let Some(result) = self.solve(grid, piece, None) {
+            return result;
+        }
+        Search::new(&mut self.table, &self.evaluator, None).root(grid, piece, self.depth)
+    }
+
+    /// Like [`AlphaBeta::think`], on a [`BitBoard`] when the board is 8x8.
+    pub fn think_game(&mut self, game: &Game) -> SearchReport {
+        match BitBoard::try_from(&game.board) {
+            Ok(bits) => self.think(&bits, game.turn),
+            Err(_) => self.think(&game.board, game.turn),
+        }
+    }
+
+    /// Iterative deepening: searches depth 1, 2, ... until `depth` is reached,
+    /// the game tree is exhausted, a win or loss is proven or the time limit
+    /// runs out. The first iteration always completes so there is a move to
+    /// play. Within the endgame threshold the exact solver gets the first half
+    /// of the time, and deepening the rest should it run out.
+    pub fn think<G: Grid + Clone>(&mut self, grid: &G, piece: Piece) -> SearchReport {
+        let start = Instant::now();
+        let deadline = self.time_limit.map(|limit| start + limit);
+        let solver_deadline = self.time_limit.map(|limit| start + limit / 2);
+        if let Some(result) = self.solve(grid, piece, solver_deadline) {
+            return SearchReport {
+                depth: grid.score().empty(),
+                nodes: result.nodes,
+                result,
+                elapsed: start.elapsed(),
+            };
+        }
+        let max_depth = self.depth.min(grid.score().empty()).max(1);
+
+        let mut search = Search::new(&mut self.table, &self.evaluator, None);
+        let mut report = SearchReport {
+            result: search.root(grid, piece, 1),
+            depth: 1,
+            nodes: search.nodes,
+            elapsed: Duration::ZERO,
+        };
+        let mut exhausted = search.exhausted;
+
+        for depth in 2..=max_depth {
+            if exhausted || report.result.score.abs() > WIN {
+                break;
+            }
+            let mut search = Search::new(&mut self.table, &self.evaluator, deadline);
+            let result = search.root(grid, piece, depth);
+            report.nodes += search.nodes;
+            if search.aborted {
+                break;
+            }
+            exhausted = search.exhausted;
+            report.result = result;
+            report.depth = depth;
+        }
+
+        report.elapsed = start.elapsed();
+        report
+    }
+
+    /// The exact result once few enough empties are left, `None` otherwise or
+    /// when `deadline` passes first.
+    fn solve<G: Grid + Clone>(
+        &self,
+        grid: &G,
+        piece: Piece,
+        deadline: Option<Instant>,
+    ) -> Option<SearchResult> {
+        if grid.score().empty() > self.endgame {
+            return None;
+        }
+        let solution = Solver::new(Mode::Exact).solve_until(grid, piece, deadline)?;
+        let best = match solution.best {
+            Some(coords) => Some(Action::Place(coords)),
+            None if grid.has_legal_move(!piece) => Some(Action::Pass),
+            None => None,
+        };
+        Some(SearchResult {
+            best,
+            score: outcome_score(solution.score),
+            pv: best.into_iter().collect(),
+            nodes: solution.nodes,
+        })
+    }
+}
+
+impl<E: Evaluator> Player for AlphaBeta<E> {
+    fn name(&self) -> &str {
+        "alpha-beta"
+    }
+
+    fn play(&mut self, game: &Game) -> Result<Action> {
+        let result = match self.time_limit {
+            Some(_) => self.think_game(game).result,
+            None => self.search_game(game),
+        };
+        Ok(result.best.unwrap_or(Action::Pass))
+    }
+}
+
+/// Final score of a finished game for `piece`.
+pub fn terminal_score<G: Grid>(grid: &G, piece: Piece) -> i32 {
+    outcome_score(grid.score().final_score().diff(piece) as i32)
+}
+
+fn outcome_score(diff: i32) -> i32 {
+    match diff {
+        d if d > 0 => WIN + d,
+        d if d < 0 => -WIN + d,
+        _ => 0,
+    }
+}
+
+struct Search<'a> {
+    table: &'a mut TranspositionTable,
+    evaluator: &'a dyn Evaluator,
+    nodes: u64,
+    deadline: Option<Instant>,
+    aborted: bool,
+    /// Stays set while no leaf had to be evaluated before the game ended.
+    exhausted: bool,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        table: &'a mut TranspositionTable,
+        evaluator: &'a dyn Evaluator,
+        deadline: Option<Instant>,
+    ) -> Self {
+        Self {
+            table,
+            evaluator,
+            nodes: 0,
+            deadline,
+            aborted: false,
+            exhausted: true,
+        }
+    }
+
+    fn root<G: Grid + Clone>(&mut self, grid: &G, piece: Piece, depth: usize) -> SearchResult {
+        let mut pv = vec![];
+        let score = self.negamax(grid, piece, depth, 0, -INFINITY, INFINITY, &mut pv);
+        if !self.aborted {
+            self.extend_pv(grid, piece, depth, &mut pv);
+        }
+        SearchResult {
+            best: pv.first().copied(),
+            score,
+            pv,
+            nodes: self.nodes,
+        }
+    }
+
+    /// Lines cut short by a transposition table hit are completed by following
+    /// the best moves stored in the table.
+    fn extend_pv<G: Grid + Clone>(
+        &self,
+        grid: &G,
+        mut piece: Piece,
+        depth: usize,
+        pv: &mut Vec<Action>,
+    ) {
+        let mut grid = grid.clone();
+        for action in pv.iter() {
+            if let Action::Place(coords) = action {
+                grid.place(*coords, piece).expect("the pv is legal");
+            }
+            piece = !piece;
+        }
+
+        let mut plies = pv.iter().filter(|a| matches!(a, Action::Place(_))).count();
+        while plies < depth {
+            if !grid.has_legal_move(piece) {
+                if !grid.has_legal_move(!piece) {
+                    break;
+                }
+                pv.push(Action::Pass);
+                piece = !piece;
+                continue;
+            }
+            let best = self.table.probe(grid.hash(piece)).and_then(|e| e.best);
+            match best {
+                Some(coords) if grid.place(coords, piece).is_ok() => {
+                    pv.push(Action::Place(coords));
+                    piece = !piece;
+                    plies += 1;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Checks the clock every [`CLOCK_INTERVAL`] nodes. Once out of time the
+    /// search unwinds and its result is thrown away.
+    fn out_of_time(&mut self) -> bool {
+        if !self.aborted && self.nodes.is_multiple_of(CLOCK_INTERVAL) {
+            self.aborted = self.deadline.is_some_and(|d| Instant::now() >= d);
+        }
+        self.aborted
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax<G: Grid + Clone>(
+        &mut self,
+        grid: &G,
+        piece: Piece,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Action>,
+    ) -> i32 {
+        self.nodes += 1;
+        pv.clear();
+        if self.out_of_time() {
+            return 0;
+        }
+
+        let key = grid.hash(piece);
+        let entry = self.table.probe(key);
+        if let Some(e) = entry.filter(|e| ply > 0 && e.depth >= depth) {
+            let cutoff = match e.bound {
+                Bound::Exact => true,
+                Bound::Lower => e.score >= beta,
+                Bound::Upper => e.score <= alpha,
+            };
+            if cutoff {
+                self.exhausted = false;
+                return e.score;
+            }
+        }
+        let alpha_start = alpha;
+
+        let mut moves = grid.legal_moves(piece);
+        if moves.is_empty() {
+            if !grid.has_legal_move(!piece) {
+                return terminal_score(grid, piece);
+            }
+            let mut line = vec![];
+            let score = -self.negamax(grid, !piece, depth, ply + 1, -beta, -alpha, &mut line);
+            pv.push(Action::Pass);
+            pv.append(&mut line);
+            return score;
+        }
+        if depth == 0 {
+            self.exhausted = false;
+            return self.evaluator.evaluate(grid, piece);
+        }
+
+        if let Some(hinted) = entry.and_then(|e| e.best) {
+            if let Some(i) = moves.iter().position(|m| m.coords == hinted) {
+                moves.swap(0, i);
+            }
+        }
+
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        let mut line = vec![];
+        for m in moves {
+            let mut child = grid.clone();
+            child
+                .place(m.coords, piece)
+                .expect("legal moves can be placed");
+            let score = -self.negamax(&child, !piece, depth - 1, ply + 1, -beta, -alpha, &mut line);
+            if self.aborted {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                best_move = Some(m.coords);
+                pv.clear();
+                pv.push(Action::Place(m.coords));
+                pv.append(&mut line);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = match best {
+            b if b <= alpha_start => Bound::Upper,
+            b if b >= beta => Bound::Lower,
+            _ => Bound::Exact,
+        };
+        self.table.store(Entry {
+            key,
+            depth,
+            bound,
+            score: best,
+            best: best_move,
+        });
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{ai::eval::Weighted, board::Board, coordinates::Coords, player::Runner};
+
+    fn minimax<G: Grid + Clone>(grid: &G, piece: Piece, depth: usize) -> i32 {
+        let moves = grid.legal_moves(piece);
+        if moves.is_empty() {
+            if !grid.has_legal_move(!piece) {
+                return terminal_score(grid, piece);
+            }
+            return -minimax(grid, !piece, depth);
+        }
+        if depth == 0 {
+            return grid.score().diff(piece) as i32;
+        }
+        moves
+            .iter()
+            .map(|m| {
+                let mut child = grid.clone();
+                child.place(m.coords, piece).unwrap();
+                -minimax(&child, !piece, depth - 1)
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_matches_plain_minimax() {
+        let board = Board::new(6).unwrap();
+        let bits = BitBoard::new();
+
+        for depth in 1..=4 {
+            let mut engine = AlphaBeta::new(depth).with_table_capacity(0);
+            assert_eq!(
+                engine.search(&board, Piece::Red).score,
+                minimax(&board, Piece::Red, depth)
+            );
+            assert_eq!(
+                engine.search(&bits, Piece::Blue).score,
+                minimax(&bits, Piece::Blue, depth)
+            );
+        }
+    }
+
+    #[test]
+    fn test_transposition_table_saves_nodes() {
+        let board = Board::new(6).unwrap();
+        let mut plain = AlphaBeta::new(5).with_table_capacity(0);
+        let mut hashed = AlphaBeta::new(5);
+
+        let without = plain.search(&board, Piece::Red);
+        let with = hashed.search(&board, Piece::Red);
+
+        assert_eq!(with.score, without.score);
+        assert!(with.nodes < without.nodes);
+        assert!(!hashed.table().is_empty());
+        assert!(plain.table().is_empty());
+    }
+
+    #[test]
+    fn test_principal_variation_is_playable() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Red;
+
+        let result = AlphaBeta::new(4).search_game(&game);
+
+        assert_eq!(result.pv.len(), 4);
+        assert_eq!(result.best, result.pv.first().copied());
+        assert!(result.nodes > 1);
+        for action in result.pv {
+            match action {
+                Action::Place(coords) => game.play(coords).map(|_| ()).unwrap(),
+                Action::Pass => panic!("nobody passes this early"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_matches_fixed_depth() {
+        let board = Board::new(6).unwrap();
+
+        let report = AlphaBeta::new(4).think(&board, Piece::Red);
+        let fixed = AlphaBeta::new(4).search(&board, Piece::Red);
+
+        assert_eq!(report.depth, 4);
+        assert_eq!(report.result.score, fixed.score);
+        assert_eq!(report.result.pv, fixed.pv);
+        assert!(report.nodes > report.result.nodes);
+    }
+
+    #[test]
+    fn test_time_limit_is_honoured() {
+        let board = Board::new(16).unwrap();
+        let limit = Duration::from_millis(50);
+
+        let report = AlphaBeta::timed(limit).think(&board, Piece::Blue);
+
+        assert!(report.result.best.is_some());
+        assert!(report.depth >= 1);
+        assert!(report.nodes_per_second() > 0);
+
+        // With the time already up, only the first depth is searched.
+        let report = AlphaBeta::timed(Duration::ZERO).think(&board, Piece::Blue);
+
+        assert!(report.result.best.is_some());
+        assert!(report.depth >= 1);
+    }
+
+    #[test]
+    fn test_stops_when_tree_is_exhausted() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in ["B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3"] {
+            game.place(m).unwrap();
+        }
+
+        let report = AlphaBeta::timed(Duration::from_secs(10)).think_game(&game);
+
+        assert!(report.depth <= 27);
+        assert!(report.elapsed < Duration::from_secs(10));
+        assert_eq!(report.result.score, WIN + 36);
+    }
+
+    #[test]
+    fn test_finds_winning_move() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in ["B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3"] {
+            game.place(m).unwrap();
+        }
+
+        let result = AlphaBeta::new(3).search_game(&game);
+
+        assert_eq!(
+            result.best,
+            Some(Action::Place(Coords::from_str("F:3").unwrap()))
+        );
+        assert_eq!(result.score, WIN + 36);
+    }
+
+    #[test]
+    fn test_endgame_threshold_solves_exactly() {
+        let mut board = Board::new(6).unwrap();
+        let mut piece = Piece::Red;
+        while board.score().empty() > 8 {
+            let moves = Grid::legal_moves(&board, piece);
+            if let Some(m) = moves.last() {
+                Grid::place(&mut board, m.coords, piece).unwrap();
+            }
+            piece = !piece;
+        }
+
+        let solved = AlphaBeta::new(1).with_endgame(8).search(&board, piece);
+        let searched = AlphaBeta::new(8).search(&board, piece);
+        let report = AlphaBeta::new(1).with_endgame(8).think(&board, piece);
+
+        assert_eq!(solved.score, searched.score);
+        assert_eq!(solved.pv.len(), 1);
+        assert_eq!(report.result, solved);
+        assert_eq!(report.depth, 8);
+    }
+
+    #[test]
+    fn test_endgame_solver_honours_time_limit() {
+        let mut board = BitBoard::new();
+        let mut piece = Piece::Red;
+        while board.score().empty() > 20 {
+            let moves = board.legal_moves(piece);
+            match moves.last() {
+                Some(m) => board.place(m.coords, piece).map(|_| ()).unwrap(),
+                None => assert!(board.has_legal_move(!piece)),
+            }
+            piece = !piece;
+        }
+        let search = AlphaBeta::new(1).with_endgame(20);
+
+        assert!(search.solve(&board, piece, Some(Instant::now())).is_none());
+
+        let report = AlphaBeta::timed(Duration::ZERO)
+            .with_endgame(20)
+            .think(&board, piece);
+
+        assert!(report.result.best.is_some());
+        assert!(report.depth >= 1 && report.depth < 20);
+    }
+
+    #[test]
+    fn test_forced_pass_is_the_best_move() {
+        // Red's only disc sits next to the last empty square, which it can't
+        // take while Blue can.
+        let mut cells = vec![Some(Piece::Blue); 36];
+        cells[0] = None;
+        cells[1] = Some(Piece::Red);
+        let board = Board::from_cells(6, &cells).unwrap();
+
+        let searched = AlphaBeta::new(2).search(&board, Piece::Red);
+        let solved = AlphaBeta::new(1).with_endgame(1).search(&board, Piece::Red);
+
+        assert_eq!(searched.best, Some(Action::Pass));
+        assert_eq!(solved.best, Some(Action::Pass));
+        assert_eq!(solved.pv.first(), Some(&Action::Pass));
+        assert_eq!(solved.score, searched.score);
+    }
+
+    #[test]
+    fn test_engine_match_between_evaluators() {
+        let mut game = Game::new(6).unwrap();
+        let weighted = AlphaBeta::new(2).with_evaluator(Weighted::standard());
+        let greedy = AlphaBeta::new(2);
+        assert_eq!(weighted.evaluator().name(), "weighted");
+
+        let result = Runner::new(weighted, greedy).run(&mut game).unwrap();
+
+        assert!(game.is_over());
+        assert_eq!(result.blue + result.red, 36);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::coordinates::Coords;
+
+/// Default number of slots. An entry takes 48 bytes, so about 3 MiB.
+pub const DEFAULT_CAPACITY: usize = 1 << 16;
+
+/// How a stored score relates to the true value of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The search failed high: the true score is at least `score`.
+    Lower,
+    /// The search failed low: the true score is at most `score`.
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: usize,
+    pub bound: Bound,
+    pub score: i32,
+    pub best: Option<Coords>,
+}
+
+/// Fixed size hash table of search results, indexed by Zobrist key. A slot
+/// holds a single entry; a new entry replaces the old one unless it is for
+/// the same position at a shallower depth.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    /// Occupied slots.
+    len: usize,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl TranspositionTable {
+    /// A table with `capacity` rounded up to a power of two slots. A capacity
+    /// of zero stores nothing.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = match capacity {
+            0 => 0,
+            c => c.next_power_of_two(),
+        };
+        Self {
+            entries: vec![None; capacity],
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|e| *e = None);
+        self.len = 0;
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.slot(key)
+            .and_then(|i| self.entries[i])
+            .filter(|e| e.key == key)
+    }
+
+    pub fn store(&mut self, entry: Entry) {
+        if let Some(i) = self.slot(entry.key) {
+            let slot = &mut self.entries[i];
+            match slot {
+                Some(old) if old.key == entry.key && old.depth > entry.depth => {}
+                Some(_) => *slot = Some(entry),
+                None => {
+                    *slot = Some(entry);
+                    self.len += 1;
+                }
+            }
+        }
+    }
+
+    fn slot(&self, key: u64) -> Option<usize> {
+        match self.entries.len() {
+            0 => None,
+            len => Some(key as usize & (len - 1)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: u64, depth: usize) -> Entry {
+        Entry {
+            key,
+            depth,
+            bound: Bound::Exact,
+            score: depth as i32,
+            best: Some(Coords::new(0, 0)),
+        }
+    }
+
+    #[test]
+    fn test_store_and_probe() {
+        let mut table = TranspositionTable::new(10);
+        assert_eq!(table.capacity(), 16);
+
+        table.store(entry(3, 2));
+
+        assert_eq!(table.probe(3), Some(entry(3, 2)));
+        assert_eq!(table.probe(19), None);
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_replacement() {
+        let mut table = TranspositionTable::new(16);
+
+        table.store(entry(3, 4));
+        table.store(entry(3, 2));
+        assert_eq!(table.probe(3), Some(entry(3, 4)));
+
+        table.store(entry(19, 1));
+        assert_eq!(table.probe(3), None);
+        assert_eq!(table.probe(19), Some(entry(19, 1)));
+        assert_eq!(table.len(), 1);
+
+        table.clear();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_zero_capacity_stores_nothing() {
+        let mut table = TranspositionTable::new(0);
+
+        table.store(entry(3, 4));
+
+        assert_eq!(table.probe(3), None);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{
+    board::{Board, BoardError, LegalMove, Score},
+    coordinates::Coords,
+    grid::Grid,
+    piece::Piece,
+    position::PositionError,
+    zobrist, Dir,
+};
+use enum_iterator::all;
+
+pub const SIZE: usize = 8;
+
+const NOT_FIRST_COL: u64 = 0xfefe_fefe_fefe_fefe;
+const NOT_LAST_COL: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+
+/// The standard 8x8 board as two masks, one bit per square in row-major
+/// order, so square `A:1` is bit 0 and `H:8` is bit 63.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    blue: u64,
+    red: u64,
+}
+
+impl Default for BitBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitBoard {
+    /// The initial position, laid out as [`Board::new`] does.
+    pub fn new() -> Self {
+        Self {
+            blue: square(Coords::new(3, 3)) | square(Coords::new(4, 4)),
+            red: square(Coords::new(3, 4)) | square(Coords::new(4, 3)),
+        }
+    }
+
+    pub fn from_masks(blue: u64, red: u64) -> Self {
+        debug_assert_eq!(blue & red, 0, "a square can't hold two pieces");
+        Self { blue, red }
+    }
+
+    /// A copy of any 8x8 grid.
+    pub fn from_grid<G: Grid + ?Sized>(grid: &G) -> Result<Self, BoardError> {
+        if grid.size() != SIZE {
+            return Err(BoardError::InvalidBoardSize(grid.size()));
+        }
+        let mut bits = BitBoard::from_masks(0, 0);
+        for index in 0..(SIZE * SIZE) as u32 {
+            match grid.piece(coords(index)) {
+                Some(Piece::Blue) => bits.blue |= 1 << index,
+                Some(Piece::Red) => bits.red |= 1 << index,
+                None => {}
+            }
+        }
+        Ok(bits)
+    }
+
+    pub fn mask(&self, piece: Piece) -> u64 {
+        match piece {
+            Piece::Blue => self.blue,
+            Piece::Red => self.red,
+        }
+    }
+
+    pub fn empty(&self) -> u64 {
+        !(self.blue | self.red)
+    }
+
+    /// Squares where `piece` may play.
+    pub fn moves(&self, piece: Piece) -> u64 {
+        let (own, other) = (self.mask(piece), self.mask(!piece));
+        let empty = self.empty();
+
+        all::<Dir>().fold(0, |moves, dir| {
+            let mut run = shift(own, dir) & other;
+            for _ in 0..5 {
+                run |= shift(run, dir) & other;
+            }
+            moves | (shift(run, dir) & empty)
+        })
+    }
+
+    /// Discs flipped when `piece` plays on the square at bit `index`.
+    pub fn flips_mask(&self, index: u32, piece: Piece) -> u64 {
+        let at = 1u64 << index;
+        if at & !self.empty() != 0 {
+            return 0;
+        }
+        all::<Dir>().fold(0, |flips, dir| flips | self.flips_dir(at, piece, dir))
+    }
+
+    fn flips_dir(&self, at: u64, piece: Piece, dir: Dir) -> u64 {
+        let (own, other) = (self.mask(piece), self.mask(!piece));
+        let mut run = 0;
+        let mut cursor = shift(at, dir);
+        while cursor & other != 0 {
+            run |= cursor;
+            cursor = shift(cursor, dir);
+        }
+        match cursor & own {
+            0 => 0,
+            _ => run,
+        }
+    }
+
+    /// Plays `piece` at bit `index` and returns the flipped discs. Nothing
+    /// changes when the move flips nothing.
+    pub fn play(&mut self, index: u32, piece: Piece) -> u64 {
+        let flips = self.flips_mask(index, piece);
+        if flips != 0 {
+            let placed = flips | (1u64 << index);
+            match piece {
+                Piece::Blue => {
+                    self.blue |= placed;
+                    self.red &= !flips;
+                }
+                Piece::Red => {
+                    self.red |= placed;
+                    self.blue &= !flips;
+                }
+            }
+        }
+        flips
+    }
+}
+
+fn square(coords: Coords) -> u64 {
+    1u64 << index(coords)
+}
+
+fn index(coords: Coords) -> u32 {
+    (coords.row * SIZE + coords.col) as u32
+}
+
+fn coords(index: u32) -> Coords {
+    Coords::new(index as usize / SIZE, index as usize % SIZE)
+}
+
+fn coords_of(bit: u64) -> Coords {
+    coords(bit.trailing_zeros())
+}
+
+/// Moves every bit one square towards `dir`, dropping those leaving the board.
+pub fn shift(mask: u64, dir: Dir) -> u64 {
+    match dir {
+        Dir::Up => mask >> 8,
+        Dir::UpRight => (mask >> 7) & NOT_FIRST_COL,
+        Dir::Right => (mask << 1) & NOT_FIRST_COL,
+        Dir::DownRight => (mask << 9) & NOT_FIRST_COL,
+        Dir::Down => mask << 8,
+        Dir::DownLeft => (mask << 7) & NOT_LAST_COL,
+        Dir::Left => (mask >> 1) & NOT_LAST_COL,
+        Dir::UpLeft => (mask >> 9) & NOT_LAST_COL,
+    }
+}
+
+/// Iterates the squares set in `mask`, lowest bit first.
+pub fn squares(mut mask: u64) -> impl Iterator<Item = Coords> {
+    std::iter::from_fn(move || match mask {
+        0 => None,
+        _ => {
+            let index = mask.trailing_zeros();
+            mask &= mask - 1;
+            Some(coords(index))
+        }
+    })
+}
+
+impl Grid for BitBoard {
+    fn size(&self) -> usize {
+        SIZE
+    }
+
+    fn piece(&self, coords: Coords) -> Option<Piece> {
+        if coords.row >= SIZE || coords.col >= SIZE {
+            return None;
+        }
+        let at = square(coords);
+        match (self.blue & at, self.red & at) {
+            (0, 0) => None,
+            (0, _) => Some(Piece::Red),
+            _ => Some(Piece::Blue),
+        }
+    }
+
+    /// Flips are listed walking outwards, direction by direction, the same
+    /// order [`Board`] reports them in.
+    fn flips(&self, coords: Coords, piece: Piece) -> Vec<Coords> {
+        if self.piece(coords).is_some() || coords.row >= SIZE || coords.col >= SIZE {
+            return vec![];
+        }
+        let at = square(coords);
+        all::<Dir>()
+            .flat_map(|dir| {
+                let run = self.flips_dir(at, piece, dir);
+                let mut cursor = at;
+                (0..run.count_ones()).map(move |_| {
+                    cursor = shift(cursor, dir);
+                    coords_of(cursor)
+                })
+            })
+            .collect()
+    }
+
+    fn legal_moves(&self, piece: Piece) -> Vec<LegalMove> {
+        squares(self.moves(piece))
+            .map(|coords| LegalMove {
+                coords,
+                flips: self.flips(coords, piece),
+            })
+            .collect()
+    }
+
+    fn has_legal_move(&self, piece: Piece) -> bool {
+        self.moves(piece) != 0
+    }
+
+    fn place(&mut self, coords: Coords, piece: Piece) -> Result<Vec<Coords>, BoardError> {
+        if coords.row >= SIZE || coords.col >= SIZE {
+            return Err(BoardError::InvalidPosition(coords));
+        }
+        if self.piece(coords).is_some() {
+            return Err(PositionError::PositionAlreadyOccupied.into());
+        }
+        let flips = self.flips(coords, piece);
+        if self.play(index(coords), piece) == 0 {
+            return Err(PositionError::IllegalMove(coords, piece).into());
+        }
+        Ok(flips)
+    }
+
+    fn score(&self) -> Score {
+        let blue = self.blue.count_ones() as usize;
+        let red = self.red.count_ones() as usize;
+        Score::new(blue, red, SIZE * SIZE - blue - red)
+    }
+
+    fn hash(&self, to_move: Piece) -> u64 {
+        let keys = |mask: u64, piece: Piece| {
+            squares(mask).fold(0, |hash, c| hash ^ zobrist::key(index(c) as usize, piece))
+        };
+        keys(self.blue, Piece::Blue)
+            ^ keys(self.red, Piece::Red)
+            ^ zobrist::size(SIZE)
+            ^ zobrist::turn(to_move)
+    }
+}
+
+impl TryFrom<&Board> for BitBoard {
+    type Error = BoardError;
+
+    fn try_from(board: &Board) -> Result<Self, Self::Error> {
+        BitBoard::from_grid(board)
+    }
+}
+
+impl From<&BitBoard> for Board {
+    fn from(bits: &BitBoard) -> Self {
+        let cells = (0..(SIZE * SIZE) as u32)
+            .map(|index| bits.piece(coords(index)))
+            .collect::<Vec<_>>();
+        Board::from_cells(SIZE, &cells).expect("an 8x8 board is always valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_initial_position_matches_board() {
+        let board = Board::new(8).unwrap();
+
+        assert_eq!(BitBoard::try_from(&board).unwrap(), BitBoard::new());
+        assert_eq!(Board::from(&BitBoard::new()).to_string(), board.to_string());
+        assert!(BitBoard::try_from(&Board::new(10).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_moves() {
+        let bits = BitBoard::new();
+        let moves = squares(bits.moves(Piece::Blue))
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(moves, vec!["C:5", "D:6", "E:3", "F:4"]);
+    }
+
+    #[test]
+    fn test_shift_does_not_wrap() {
+        let h1 = square(Coords::from_str("A:8").unwrap());
+        let a2 = square(Coords::from_str("B:1").unwrap());
+
+        assert_eq!(shift(h1, Dir::Right), 0);
+        assert_eq!(shift(h1, Dir::DownRight), 0);
+        assert_eq!(shift(a2, Dir::Left), 0);
+        assert_eq!(shift(a2, Dir::UpLeft), 0);
+        assert_eq!(shift(a2, Dir::Up), 1);
+    }
+
+    #[test]
+    fn test_place_rejects_illegal_moves() {
+        let mut bits = BitBoard::new();
+
+        assert!(bits.place(Coords::new(0, 0), Piece::Blue).is_err());
+        assert!(bits.place(Coords::new(3, 3), Piece::Blue).is_err());
+        assert!(bits.place(Coords::new(8, 0), Piece::Blue).is_err());
+        assert_eq!(bits, BitBoard::new());
+    }
+
+    #[test]
+    fn test_random_games_match_board() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let mut board = Board::new(8).unwrap();
+            let mut bits = BitBoard::new();
+            let mut piece = Piece::Red;
+
+            loop {
+                let moves = Grid::legal_moves(&board, piece);
+                assert_eq!(moves, bits.legal_moves(piece));
+                if moves.is_empty() {
+                    if !bits.has_legal_move(!piece) {
+                        break;
+                    }
+                    piece = !piece;
+                    continue;
+                }
+                let coords = moves.choose(&mut rng).unwrap().coords;
+                assert_eq!(
+                    Grid::place(&mut board, coords, piece).unwrap(),
+                    bits.place(coords, piece).unwrap()
+                );
+                assert_eq!(Grid::score(&board), bits.score());
+                assert_eq!(Grid::hash(&board, piece), bits.hash(piece));
+                piece = !piece;
+            }
+
+            assert_eq!(BitBoard::try_from(&board).unwrap(), bits);
+        }
+    }
+}
@@ -1,44 +1,71 @@
-use crate::{coordinates::Coords, piece::Piece, position::Position};
+use crate::{
+    coordinates::Coords,
+    grid::Grid,
+    piece::Piece,
+    position::{Position, PositionError},
+    stability, zobrist, Wrap,
+};
 use std::{
     cell::RefCell,
+    cmp::Ordering,
+    collections::HashSet,
     fmt::{Debug, Display},
-    ops::{Deref, DerefMut},
+    ops::Deref,
     rc::Rc,
 };
 
 pub const EMPTY_POSITION: char = ' ';
 
+/// Largest board size accepted from game files, which fits every row in a
+/// single letter.
+pub const MAX_SIZE: usize = 26;
+
 pub type MatrixPointer = Rc<RefCell<Matrix>>;
 
-#[derive(Debug)]
-pub struct Matrix(Box<[char]>);
+/// The squares of a board, along with their Zobrist hash which every
+/// [`Matrix::write`] keeps up to date.
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    cells: Box<[char]>,
+    hash: u64,
+}
 
 impl Deref for Matrix {
     type Target = [char];
 
     fn deref(&self) -> &Self::Target {
-        self.0.as_ref()
-    }
-}
-
-impl DerefMut for Matrix {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.0.as_mut()
+        self.cells.as_ref()
     }
 }
 
 impl Matrix {
     pub fn size(&self) -> usize {
-        (self.0.len() as f64).sqrt().round() as usize
+        (self.cells.len() as f64).sqrt().round() as usize
     }
 
     pub fn new(size: usize) -> Self {
-        Matrix(vec![EMPTY_POSITION; size * size].into_boxed_slice())
+        Matrix {
+            cells: vec![EMPTY_POSITION; size * size].into_boxed_slice(),
+            hash: zobrist::size(size),
+        }
     }
 
     pub fn write(&mut self, coords: Coords, c: char) {
-        let size = self.size();
-        self[(size * coords.row) + coords.col] = c;
+        let index = (self.size() * coords.row) + coords.col;
+        let Wrap(old) = Wrap::<Option<Piece>>::from(self.cells[index]);
+        let Wrap(new) = Wrap::<Option<Piece>>::from(c);
+        if let Some(old) = old {
+            self.hash ^= zobrist::key(index, old);
+        }
+        if let Some(new) = new {
+            self.hash ^= zobrist::key(index, new);
+        }
+        self.cells[index] = c;
+    }
+
+    /// Zobrist hash of the pieces, without the side to move.
+    pub fn hash(&self) -> u64 {
+        self.hash
     }
 
     pub fn read(&self, coords: Coords) -> char {
@@ -53,6 +80,77 @@ pub enum BoardError {
 
     #[error("Invalid board size: {0}. The size must be a number greater than 4 and even.")]
     InvalidBoardSize(usize),
+
+    #[error("Invalid number of cells: {1}. A board of size {0} has {} cells.", .0 * .0)]
+    InvalidCellCount(usize, usize),
+
+    #[error(transparent)]
+    Position(#[from] PositionError),
+}
+
+/// A square where a piece may be placed, along with the squares it would flip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegalMove {
+    pub coords: Coords,
+    pub flips: Vec<Coords>,
+}
+
+/// Disc counts of a board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Score {
+    blue: usize,
+    red: usize,
+    empty: usize,
+}
+
+impl Score {
+    pub fn new(blue: usize, red: usize, empty: usize) -> Self {
+        Self { blue, red, empty }
+    }
+
+    pub fn get(&self, piece: Piece) -> usize {
+        match piece {
+            Piece::Blue => self.blue,
+            Piece::Red => self.red,
+        }
+    }
+
+    pub fn empty(&self) -> usize {
+        self.empty
+    }
+
+    /// Discs of `piece` minus discs of its opponent.
+    pub fn diff(&self, piece: Piece) -> isize {
+        self.get(piece) as isize - self.get(!piece) as isize
+    }
+
+    /// The side with more discs, `None` when even.
+    pub fn leader(&self) -> Option<Piece> {
+        match self.blue.cmp(&self.red) {
+            Ordering::Greater => Some(Piece::Blue),
+            Ordering::Less => Some(Piece::Red),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// The score of a finished game: empty squares are awarded to the winner,
+    /// or split evenly on a draw.
+    pub fn final_score(&self) -> Score {
+        match self.leader() {
+            Some(Piece::Blue) => Score::new(self.blue + self.empty, self.red, 0),
+            Some(Piece::Red) => Score::new(self.blue, self.red + self.empty, 0),
+            None => {
+                let half = self.empty / 2;
+                Score::new(self.blue + half, self.red + self.empty - half, 0)
+            }
+        }
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Blue {} - Red {}", self.blue, self.red)
+    }
 }
 
 #[derive(Debug)]
@@ -61,6 +159,25 @@ pub struct Board {
     matrix: MatrixPointer,
 }
 
+/// Cloning copies the squares: the clone and the original evolve independently.
+impl Clone for Board {
+    fn clone(&self) -> Self {
+        Board {
+            size: self.size,
+            matrix: Rc::new(RefCell::new(self.matrix.borrow().clone())),
+        }
+    }
+}
+
+/// Boards are equal when they have the same discs on the same squares.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && **self.matrix.borrow() == **other.matrix.borrow()
+    }
+}
+
+impl Eq for Board {}
+
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let x = self.matrix.borrow();
@@ -100,12 +217,118 @@ impl Board {
         })
     }
 
+    /// Builds a board of `size` from its cells in row-major order.
+    pub fn from_cells(size: usize, cells: &[Option<Piece>]) -> Result<Board, BoardError> {
+        if size <= 4 || (size % 2 == 1) {
+            return Err(BoardError::InvalidBoardSize(size));
+        }
+        if size.checked_mul(size) != Some(cells.len()) {
+            return Err(BoardError::InvalidCellCount(size, cells.len()));
+        }
+
+        let mut data = Matrix::new(size);
+        for (index, cell) in cells.iter().enumerate() {
+            if let Some(piece) = cell {
+                data.write(Coords::new(index / size, index % size), (*piece).into());
+            }
+        }
+
+        Ok(Board {
+            size,
+            matrix: Rc::new(RefCell::new(data)),
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
     pub fn get(&self, coords: Coords) -> Result<Position, BoardError> {
         if self.size > coords.row && self.size > coords.col {
             return Ok(Position::new(self.matrix.clone(), coords));
         }
         Err(BoardError::InvalidPosition(coords))
     }
+
+    /// Zobrist hash of the position with `to_move` to play.
+    pub fn hash(&self, to_move: Piece) -> u64 {
+        self.matrix.borrow().hash() ^ zobrist::turn(to_move)
+    }
+
+    pub fn count(&self, piece: Piece) -> usize {
+        self.score().get(piece)
+    }
+
+    pub fn score(&self) -> Score {
+        let blue: char = Piece::Blue.into();
+        let red: char = Piece::Red.into();
+        self.matrix
+            .borrow()
+            .iter()
+            .fold(Score::default(), |mut score, &c| {
+                match c {
+                    c if c == blue => score.blue += 1,
+                    c if c == red => score.red += 1,
+                    _ => score.empty += 1,
+                }
+                score
+            })
+    }
+
+    /// Every square where `piece` could legally play, in row-major order.
+    pub fn legal_moves(&self, piece: Piece) -> Vec<LegalMove> {
+        let size = self.size;
+        (0..size)
+            .flat_map(|row| (0..size).map(move |col| Coords::new(row, col)))
+            .filter_map(|coords| {
+                let flips = Position::new(self.matrix.clone(), coords).flips(piece);
+                match flips.is_empty() {
+                    true => None,
+                    false => Some(LegalMove { coords, flips }),
+                }
+            })
+            .collect()
+    }
+
+    /// Discs of `piece` that can never be flipped again, either anchored to
+    /// the edges or sitting on full lines.
+    pub fn stable(&self, piece: Piece) -> HashSet<Coords> {
+        stability::stable(self, piece)
+    }
+}
+
+impl Grid for Board {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn piece(&self, coords: Coords) -> Option<Piece> {
+        self.get(coords).ok().and_then(|p| p.piece())
+    }
+
+    fn flips(&self, coords: Coords, piece: Piece) -> Vec<Coords> {
+        self.get(coords).map(|p| p.flips(piece)).unwrap_or_default()
+    }
+
+    fn legal_moves(&self, piece: Piece) -> Vec<LegalMove> {
+        Board::legal_moves(self, piece)
+    }
+
+    fn place(&mut self, coords: Coords, piece: Piece) -> Result<Vec<Coords>, BoardError> {
+        Ok(self.get(coords)?.place(piece)?)
+    }
+
+    fn score(&self) -> Score {
+        Board::score(self)
+    }
+
+    fn hash(&self, to_move: Piece) -> u64 {
+        Board::hash(self, to_move)
+    }
+
+    fn stable(&self, piece: Piece) -> HashSet<Coords> {
+        Board::stable(self, piece)
+    }
 }
 
 #[cfg(test)]
@@ -119,8 +342,9 @@ mod tests {
     fn test_matrix() {
         let mut m = Matrix::new(8);
         let c = m.read(Coords::new(7, 7));
-        let c = m.write(Coords::new(7, 7), 'B');
-        dbg!(m);
+        m.write(Coords::new(7, 7), 'B');
+        assert_eq!(c, EMPTY_POSITION);
+        assert_eq!(m.read(Coords::new(7, 7)), 'B');
     }
 
     #[test]
@@ -139,7 +363,7 @@ mod tests {
             ("E:3", Piece::Blue),
             ("D:3", Piece::Red),
             ("C:3", Piece::Blue),
-            ("D:2", Piece::Red),
+            ("F:3", Piece::Red),
         ];
         let board = Board::new(8).unwrap();
         //println!("{}", board);
@@ -153,4 +377,130 @@ mod tests {
             //println!("{}", board);
         }
     }
+
+    #[test]
+    fn test_from_cells() {
+        let mut cells = vec![None; 36];
+        cells[0] = Some(Piece::Red);
+        cells[35] = Some(Piece::Blue);
+
+        let board = Board::from_cells(6, &cells).unwrap();
+
+        assert_eq!(Grid::piece(&board, Coords::new(0, 0)), Some(Piece::Red));
+        assert_eq!(Grid::piece(&board, Coords::new(5, 5)), Some(Piece::Blue));
+        assert_eq!(board.score(), Score::new(1, 1, 34));
+        assert!(matches!(
+            Board::from_cells(6, &cells[1..]),
+            Err(BoardError::InvalidCellCount(6, 35))
+        ));
+        assert!(matches!(
+            Board::from_cells(usize::MAX - 1, &[None; 4]),
+            Err(BoardError::InvalidCellCount(_, 4))
+        ));
+        assert!(matches!(
+            Board::from_cells(7, &[None; 49]),
+            Err(BoardError::InvalidBoardSize(7))
+        ));
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let board = Board::new(8).unwrap();
+        let copy = board.clone();
+
+        board
+            .get(Coords::from_str("E:3").unwrap())
+            .unwrap()
+            .place(Piece::Blue)
+            .unwrap();
+
+        assert_eq!(copy.to_string(), Board::new(8).unwrap().to_string());
+        assert_ne!(copy.to_string(), board.to_string());
+    }
+
+    #[test]
+    fn test_hash_is_updated_incrementally() {
+        let board = Board::new(8).unwrap();
+        let start = board.hash(Piece::Red);
+        assert_eq!(start, zobrist::hash(&board, Piece::Red));
+        assert_ne!(start, board.hash(Piece::Blue));
+
+        board
+            .get(Coords::from_str("E:3").unwrap())
+            .unwrap()
+            .place(Piece::Blue)
+            .unwrap();
+        assert_eq!(board.hash(Piece::Red), zobrist::hash(&board, Piece::Red));
+        assert_ne!(board.hash(Piece::Red), start);
+
+        board
+            .get(Coords::from_str("E:3").unwrap())
+            .unwrap()
+            .remove()
+            .unwrap();
+        board
+            .get(Coords::from_str("E:4").unwrap())
+            .unwrap()
+            .flip()
+            .unwrap();
+        assert_eq!(board.hash(Piece::Red), start);
+    }
+
+    #[test]
+    fn test_count() {
+        let board = Board::new(8).unwrap();
+        board
+            .get(Coords::from_str("E:3").unwrap())
+            .unwrap()
+            .place(Piece::Blue)
+            .unwrap();
+
+        assert_eq!(board.count(Piece::Blue), 4);
+        assert_eq!(board.count(Piece::Red), 1);
+    }
+
+    #[test]
+    fn test_score() {
+        let board = Board::new(6).unwrap();
+        let score = board.score();
+
+        assert_eq!(score, Score::new(2, 2, 32));
+        assert_eq!(score.leader(), None);
+        assert_eq!(score.to_string(), "Blue 2 - Red 2");
+    }
+
+    #[test]
+    fn test_final_score() {
+        assert_eq!(Score::new(20, 30, 14).final_score(), Score::new(20, 44, 0));
+        assert_eq!(Score::new(33, 29, 2).final_score(), Score::new(35, 29, 0));
+        assert_eq!(Score::new(30, 30, 4).final_score(), Score::new(32, 32, 0));
+        assert_eq!(Score::new(30, 30, 4).diff(Piece::Red), 0);
+        assert_eq!(Score::new(20, 30, 14).diff(Piece::Blue), -10);
+    }
+
+    #[test]
+    fn test_legal_moves_initial_position() {
+        let board = Board::new(8).unwrap();
+        let moves = board.legal_moves(Piece::Blue);
+
+        assert_eq!(
+            moves
+                .iter()
+                .map(|m| m.coords.to_string())
+                .collect::<Vec<_>>(),
+            vec!["C:5", "D:6", "E:3", "F:4"]
+        );
+        assert_eq!(moves[0].flips, vec![Coords::from_str("D:5").unwrap()]);
+        assert_eq!(board.legal_moves(Piece::Red).len(), 4);
+    }
+
+    #[test]
+    fn test_legal_moves_do_not_mutate() {
+        let board = Board::new(6).unwrap();
+        let before = board.to_string();
+
+        board.legal_moves(Piece::Red);
+
+        assert_eq!(board.to_string(), before);
+    }
 }
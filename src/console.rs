@@ -0,0 +1,189 @@
+use crate::{
+    board::Board,
+    coordinates::{Coords, RowNumber},
+    game::Game,
+};
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+const DEFAULT_BOARD_SIZE: usize = 8;
+
+/// Line based human-vs-human game, reading moves such as `D:3` from `input`.
+pub struct Console<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    clear: bool,
+}
+
+impl<R: BufRead, W: Write> Console<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            clear: false,
+        }
+    }
+
+    /// Clears the terminal before drawing each turn.
+    pub fn clear_screen(mut self, clear: bool) -> Self {
+        self.clear = clear;
+        self
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        let mut game = match self.choose_game()? {
+            Some(game) => game,
+            None => return Ok(()),
+        };
+        let mut notice = format!("{} starts.", game.turn);
+
+        while !game.is_over() {
+            if self.clear {
+                clearscreen::clear()?;
+            }
+            self.draw(&game.board)?;
+            writeln!(self.output, "{}", game.board.score())?;
+            writeln!(self.output, "{}", notice)?;
+            let line = match self.prompt(&format!("{} to move (e.g. D:3): ", game.turn))? {
+                Some(line) => line,
+                None => return Ok(()),
+            };
+
+            notice = match line.as_str() {
+                "" => String::new(),
+                "quit" | "exit" => return Ok(()),
+                "undo" => match game.undo()? {
+                    Some(undone) => format!("Took back {}.", undone),
+                    None => "Nothing to undo.".to_string(),
+                },
+                coords => match game.place(coords) {
+                    Ok(resume) => match resume.passed() {
+                        Some(passed) => {
+                            format!("{}\n{} has no legal move and passes.", resume, passed)
+                        }
+                        None => resume.to_string(),
+                    },
+                    Err(e) => format!("Rejected: {}", e),
+                },
+            };
+        }
+
+        if self.clear {
+            clearscreen::clear()?;
+        }
+        self.draw(&game.board)?;
+        writeln!(self.output, "{}", notice)?;
+        let result = game.result().expect("the game is over");
+        writeln!(
+            self.output,
+            "Game over. Blue {} - Red {}",
+            result.blue, result.red
+        )?;
+        match result.winner {
+            Some(winner) => writeln!(self.output, "{} wins!", winner)?,
+            None => writeln!(self.output, "It's a draw!")?,
+        }
+        Ok(())
+    }
+
+    fn choose_game(&mut self) -> Result<Option<Game>> {
+        loop {
+            let prompt = format!("Board size [{}]: ", DEFAULT_BOARD_SIZE);
+            let line = match self.prompt(&prompt)? {
+                Some(line) => line,
+                None => return Ok(None),
+            };
+            let size = match line.as_str() {
+                "" => DEFAULT_BOARD_SIZE,
+                s => match s.parse() {
+                    Ok(size) => size,
+                    Err(_) => {
+                        writeln!(self.output, "'{}' is not a number.", s)?;
+                        continue;
+                    }
+                },
+            };
+            match Game::new(size) {
+                Ok(game) => return Ok(Some(game)),
+                Err(e) => writeln!(self.output, "{}", e)?,
+            }
+        }
+    }
+
+    /// Reads one trimmed line, `None` once the input is exhausted.
+    fn prompt(&mut self, message: &str) -> Result<Option<String>> {
+        write!(self.output, "{}", message)?;
+        self.output.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim().to_string()))
+    }
+
+    fn draw(&mut self, board: &Board) -> Result<()> {
+        let label = RowNumber::new(board.size() - 1).to_string().len();
+        let cell = board.size().to_string().len();
+        let padding = " ".repeat(cell - 1);
+        write!(self.output, "{:label$}", "")?;
+        for col in 0..board.size() {
+            write!(self.output, " {:>cell$}", col + 1)?;
+        }
+        writeln!(self.output)?;
+        for row in 0..board.size() {
+            write!(self.output, "{:>label$}", RowNumber::new(row).to_string())?;
+            for col in 0..board.size() {
+                let position = board.get(Coords::new(row, col))?;
+                write!(self.output, " {}{}", padding, position)?;
+            }
+            writeln!(self.output)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn run(input: &str) -> String {
+        let mut output = vec![];
+        Console::new(Cursor::new(input), &mut output).run().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_board_size_is_asked_again() {
+        let output = run("3\nx\n6\nquit\n");
+
+        assert!(output.contains("Invalid board size: 3"));
+        assert!(output.contains("'x' is not a number."));
+        assert!(output.contains("starts."));
+    }
+
+    #[test]
+    fn test_rejected_moves_are_reported() {
+        let output = run("\nA:1\nA1\n");
+
+        assert!(output.contains("Rejected: "));
+        assert!(output.contains("no disc would be flipped"));
+        assert!(output.contains("Invalid coordinates format: 'A1'"));
+    }
+
+    #[test]
+    fn test_column_zero_is_rejected() {
+        let output = run("6\nB:0\n");
+
+        assert!(output.contains("Rejected: Invalid coordinates format: 'B:0'"));
+    }
+
+    #[test]
+    fn test_board_has_row_and_column_labels() {
+        let output = run("6\n");
+
+        assert!(output.contains(" 1 2 3 4 5 6"));
+        assert!(output.lines().any(|l| l.starts_with('F')));
+    }
+}
@@ -19,7 +19,7 @@ pub enum CoordinatesError {
     ParseError(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Coordinates {
     pub row: usize,
     pub col: usize,
@@ -131,12 +131,16 @@ impl FromStr for Coordinates {
             .captures(s)
             .ok_or(CoordinatesError::ParseError(s.to_string()))?;
 
-        let col: usize = captures
+        // Columns count from 1, and a number too large for usize is no column.
+        let col = match captures
             .name("col")
             .expect("a capture with name col was expected")
             .as_str()
-            .parse()
-            .expect("Failed to parse col. a number was expected");
+            .parse::<usize>()
+        {
+            Ok(col) if col > 0 => col,
+            _ => return Err(CoordinatesError::ParseError(s.to_string())),
+        };
 
         let row = RowNumber::from_str(
             captures
@@ -152,6 +156,85 @@ impl FromStr for Coordinates {
     }
 }
 
+/// How coordinates are written down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// The crate's own `ROW:COL`, a letter for the row and a number for the
+    /// column: `D:3`.
+    #[default]
+    RowCol,
+    /// Standard Othello notation, a letter for the column and a number for the
+    /// row: `c4`. Black is Red, whose initial discs stand on `d5` and `e4`.
+    Standard,
+}
+
+impl Notation {
+    fn pattern(self) -> &'static str {
+        match self {
+            Notation::RowCol => r"(?P<row>[A-Za-z]+):(?P<col>\d+)",
+            Notation::Standard => r"(?P<col>[A-Za-z]+)(?P<row>\d+)",
+        }
+    }
+
+    pub fn parse(self, s: &str) -> Result<Coords, CoordinatesError> {
+        match self {
+            Notation::RowCol => Coords::from_str(s),
+            Notation::Standard => {
+                let regex = Regex::new(&format!(r"\A{}\z", self.pattern())).expect("Invalid regex");
+                let captures = regex
+                    .captures(s)
+                    .ok_or(CoordinatesError::ParseError(s.to_string()))?;
+                let col = RowNumber::from_str(&captures["col"])?;
+                match captures["row"].parse::<usize>() {
+                    Ok(row) if row > 0 => Ok(Coords::new(row - 1, col.into())),
+                    _ => Err(CoordinatesError::ParseError(s.to_string())),
+                }
+            }
+        }
+    }
+
+    pub fn format(self, coords: Coords) -> String {
+        match self {
+            Notation::RowCol => coords.to_string(),
+            Notation::Standard => format!(
+                "{}{}",
+                RowNumber(coords.col).to_string().to_lowercase(),
+                coords.row + 1
+            ),
+        }
+    }
+
+    /// Parses a list of moves, either run together as in `f5d6c3` or apart
+    /// with spaces or commas as in `D:3, C:4`.
+    pub fn parse_transcript(self, s: &str) -> Result<Vec<Coords>, CoordinatesError> {
+        let regex = Regex::new(self.pattern()).expect("Invalid regex");
+        let mut moves = vec![];
+        let mut end = 0;
+        for m in regex.find_iter(s) {
+            let between = &s[end..m.start()];
+            if !between.chars().all(|c| c.is_whitespace() || c == ',') {
+                return Err(CoordinatesError::ParseError(between.trim().to_string()));
+            }
+            moves.push(self.parse(m.as_str())?);
+            end = m.end();
+        }
+        match s[end..].trim() {
+            "" => Ok(moves),
+            rest => Err(CoordinatesError::ParseError(rest.to_string())),
+        }
+    }
+
+    /// Writes moves the way published games do: run together in standard
+    /// notation, separated by spaces otherwise.
+    pub fn transcript(self, moves: &[Coords]) -> String {
+        let moves = moves.iter().map(|c| self.format(*c)).collect::<Vec<_>>();
+        match self {
+            Notation::RowCol => moves.join(" "),
+            Notation::Standard => moves.concat(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub struct RowNumber(usize);
 
@@ -178,8 +261,10 @@ impl FromStr for RowNumber {
 
         let mut res: usize = 0;
         for c in s.chars() {
-            res *= 26;
-            res += ((c.to_ascii_uppercase() as u8) - (b'A') + 1) as usize;
+            res = res
+                .checked_mul(26)
+                .and_then(|r| r.checked_add(((c.to_ascii_uppercase() as u8) - (b'A') + 1) as usize))
+                .ok_or(CoordinatesError::ParseError(s.to_string()))?;
         }
 
         Ok(RowNumber(res - 1))
@@ -285,6 +370,77 @@ mod tests {
         )
     }
 
+    #[rstest]
+    #[case("B:0")]
+    #[case("A:99999999999999999999999")]
+    fn test_out_of_range_coordinates_from_str(#[case] input: &str) {
+        assert_eq!(
+            Coords::from_str(input).unwrap_err(),
+            CoordinatesError::ParseError(input.to_string())
+        );
+    }
+
+    #[rstest]
+    fn test_overflowing_row_identifier_parse() {
+        let row = "Z".repeat(20);
+
+        assert_eq!(
+            RowNumber::from_str(&row).unwrap_err(),
+            CoordinatesError::ParseError(row)
+        );
+    }
+
+    #[rstest]
+    #[case("a1", Coords{row: 0, col: 0})]
+    #[case("f5", Coords{row: 4, col: 5})]
+    #[case("D6", Coords{row: 5, col: 3})]
+    #[case("aa12", Coords{row: 11, col: 26})]
+    fn test_standard_notation(#[case] input: &str, #[case] output: Coords) {
+        assert_eq!(Notation::Standard.parse(input).unwrap(), output);
+        assert_eq!(
+            Notation::Standard.format(output),
+            input.to_ascii_lowercase()
+        );
+    }
+
+    #[rstest]
+    #[case("f")]
+    #[case("5f")]
+    #[case("f0")]
+    #[case("F:5")]
+    fn test_invalid_standard_notation(#[case] input: &str) {
+        assert_eq!(
+            Notation::Standard.parse(input).unwrap_err(),
+            CoordinatesError::ParseError(input.to_string())
+        );
+    }
+
+    #[test]
+    fn test_transcripts() {
+        let moves = Notation::Standard.parse_transcript("f5d6c3d3c4").unwrap();
+
+        assert_eq!(moves.len(), 5);
+        assert_eq!(moves[1], Coords::new(5, 3));
+        assert_eq!(Notation::Standard.transcript(&moves), "f5d6c3d3c4");
+        assert_eq!(
+            Notation::Standard.parse_transcript(" f5 d6, c3 ").unwrap(),
+            moves[..3]
+        );
+        assert_eq!(Notation::RowCol.transcript(&moves[..2]), "E:6 F:4");
+        assert_eq!(
+            Notation::RowCol.parse_transcript("E:6, F:4").unwrap(),
+            moves[..2]
+        );
+        assert_eq!(
+            Notation::Standard.parse_transcript("f5 ?? d6").unwrap_err(),
+            CoordinatesError::ParseError("??".to_string())
+        );
+        assert_eq!(
+            Notation::Standard.parse_transcript("f5d6x").unwrap_err(),
+            CoordinatesError::ParseError("x".to_string())
+        );
+    }
+
     #[test]
     fn test_coordinates_iterator() {
         let c = Coords::from_str("A:1").unwrap();
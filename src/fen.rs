@@ -0,0 +1,208 @@
+//! A position on a single line, like chess's FEN: the board size, the rows
+//! from `A` down separated by `/`, and the side to move.
+//!
+//! ```text
+//! 6 ------/------/--BR--/--RB--/------/------ R
+//! ```
+//!
+//! Discs are written with [`Piece`]'s characters, `B` and `R`, and empty
+//! squares with `-`.
+
+use crate::{
+    board::Board,
+    coordinates::{Coords, RowNumber},
+    game::Game,
+    grid::Grid,
+    piece::Piece,
+};
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+const EMPTY: char = '-';
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FenError {
+    #[error("expected a size, the rows and the side to move, found '{0}'")]
+    InvalidFormat(String),
+
+    #[error("invalid board size: '{0}'. The size must be a number greater than 4 and even.")]
+    InvalidSize(String),
+
+    #[error("a board of size {0} has {0} rows, found {1}")]
+    RowCount(usize, usize),
+
+    #[error("row {0} has {1} squares, {2} were expected")]
+    RowLength(RowNumber, usize, usize),
+
+    #[error("invalid square '{1}' at {0}: expected B, R or -")]
+    InvalidSquare(Coords, char),
+
+    #[error("invalid side to move: '{0}'. Expected B or R.")]
+    InvalidSide(String),
+}
+
+/// A board and whose turn it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fen {
+    pub board: Board,
+    pub to_move: Piece,
+}
+
+impl Fen {
+    pub fn new(board: Board, to_move: Piece) -> Self {
+        Self { board, to_move }
+    }
+
+    /// The current position of `game`.
+    pub fn from_game(game: &Game) -> Self {
+        Self::new(game.board.clone(), game.turn)
+    }
+
+    /// A game starting from this position, with no pieces placed behind it.
+    /// See [`Game::from_position`] for positions where the side to move
+    /// cannot play.
+    pub fn to_game(&self) -> Game {
+        Game::from_position(self.board.clone(), self.to_move)
+    }
+}
+
+fn piece(c: char) -> Option<Piece> {
+    match c {
+        'B' | 'R' => Some(Piece::from(c)),
+        _ => None,
+    }
+}
+
+impl Display for Fen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = self.board.size();
+        let rows = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| {
+                        Grid::piece(&self.board, Coords::new(row, col)).map_or(EMPTY, char::from)
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "{} {} {}",
+            size,
+            rows.join("/"),
+            char::from(self.to_move)
+        )
+    }
+}
+
+impl FromStr for Fen {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let [size, rows, side] = fields[..] else {
+            return Err(FenError::InvalidFormat(s.to_string()));
+        };
+
+        let size = match size.parse::<usize>() {
+            Ok(n) if n > 4 && n % 2 == 0 => n,
+            _ => return Err(FenError::InvalidSize(size.to_string())),
+        };
+        let rows = rows.split('/').collect::<Vec<_>>();
+        if rows.len() != size {
+            return Err(FenError::RowCount(size, rows.len()));
+        }
+        let mut cells = Vec::with_capacity(size * size);
+        for (row, squares) in rows.iter().enumerate() {
+            let count = squares.chars().count();
+            if count != size {
+                return Err(FenError::RowLength(RowNumber::new(row), count, size));
+            }
+            for (col, c) in squares.chars().enumerate() {
+                match (c, piece(c)) {
+                    (EMPTY, _) => cells.push(None),
+                    (_, Some(piece)) => cells.push(Some(piece)),
+                    _ => return Err(FenError::InvalidSquare(Coords::new(row, col), c)),
+                }
+            }
+        }
+        let to_move = side
+            .chars()
+            .next()
+            .filter(|_| side.len() == 1)
+            .and_then(piece)
+            .ok_or_else(|| FenError::InvalidSide(side.to_string()))?;
+
+        let board = Board::from_cells(size, &cells).expect("the size and cells were checked");
+        Ok(Self::new(board, to_move))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    const INITIAL: &str = "6 ------/------/--BR--/--RB--/------/------ R";
+
+    #[rstest]
+    fn test_initial_position() {
+        let fen = Fen::new(Board::new(6).unwrap(), Piece::Red);
+
+        assert_eq!(fen.to_string(), INITIAL);
+        assert_eq!(Fen::from_str(INITIAL).unwrap(), fen);
+    }
+
+    #[rstest]
+    fn test_game_round_trip() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+        for m in ["E:3", "F:3", "G:3", "C:4"] {
+            game.place(m).unwrap();
+        }
+        let fen = Fen::from_game(&game);
+        let read = Fen::from_str(&fen.to_string()).unwrap().to_game();
+
+        assert_eq!(read.board, game.board);
+        assert_eq!(read.turn, game.turn);
+        assert!(read.moves().is_empty());
+        assert_eq!(read.legal_moves(), game.legal_moves());
+    }
+
+    #[rstest]
+    fn test_positions_without_moves() {
+        let game = Fen::from_str("6 RB----/------/------/------/------/------ B")
+            .unwrap()
+            .to_game();
+        assert!(game.moves()[0].is_pass());
+        assert_eq!(game.turn, Piece::Red);
+        assert!(!game.is_over());
+
+        let game = Fen::from_str("6 RR----/------/------/------/------/------ B")
+            .unwrap()
+            .to_game();
+        assert!(game.is_over());
+        assert_eq!(game.result().unwrap().winner, Some(Piece::Red));
+    }
+
+    #[rstest]
+    #[case("6 ------/------/--BR--/--RB--/------/------", FenError::InvalidFormat("6 ------/------/--BR--/--RB--/------/------".to_string()))]
+    #[case("7 ------/------/--BR--/--RB--/------/------ R", FenError::InvalidSize("7".to_string()))]
+    #[case("4 ----/-BR-/-RB-/---- R", FenError::InvalidSize("4".to_string()))]
+    #[case("six ------/------/--BR--/--RB--/------/------ R", FenError::InvalidSize("six".to_string()))]
+    #[case("6 ------/------/--BR--/--RB--/------ R", FenError::RowCount(6, 5))]
+    #[case(
+        "6 ------/------/--BR--/--RB-/------/------ R",
+        FenError::RowLength(RowNumber::new(3), 5, 6)
+    )]
+    #[case(
+        "6 ------/------/--BX--/--RB--/------/------ R",
+        FenError::InvalidSquare(Coords::new(2, 3), 'X')
+    )]
+    #[case("6 ------/------/--BR--/--RB--/------/------ G", FenError::InvalidSide("G".to_string()))]
+    #[case("6 ------/------/--BR--/--RB--/------/------ RB", FenError::InvalidSide("RB".to_string()))]
+    fn test_invalid_positions(#[case] input: &str, #[case] error: FenError) {
+        assert_eq!(Fen::from_str(input).unwrap_err(), error);
+    }
+}
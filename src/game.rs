@@ -1,29 +1,141 @@
-use crate::{board::Board, coordinates::Coords, piece::Piece};
+use crate::{
+    board::{Board, LegalMove, Score},
+    coordinates::{Coords, Notation},
+    piece::Piece,
+};
 use anyhow::Result;
 use std::{fmt::Display, str::FromStr};
 
+#[derive(Debug, thiserror::Error)]
+pub enum GameError {
+    #[error("The game is over")]
+    GameOver,
+
+    #[error("Invalid ply: {0}. There are {1} plies to travel through.")]
+    InvalidPly(usize, usize),
+
+    #[error("Standard transcripts open with Red, but {0} is to move")]
+    RedOpens(Piece),
+}
+
 pub struct Game {
     pub turn: Piece,
     pub board: Board,
     moves: Vec<Move>,
+    undone: Vec<Coords>,
+    result: Option<GameResult>,
+}
+
+/// Final disc counts of a finished game, with empty squares awarded to the
+/// winner. `winner` is `None` on a draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GameResult {
+    pub winner: Option<Piece>,
+    pub blue: usize,
+    pub red: usize,
+}
+
+impl GameResult {
+    fn new(board: &Board) -> Self {
+        let score = board.score().final_score();
+        Self {
+            winner: score.leader(),
+            blue: score.get(Piece::Blue),
+            red: score.get(Piece::Red),
+        }
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.winner.is_none()
+    }
 }
 
+/// What happened when a piece was placed: the discs it flipped, the score
+/// right after the move and whether it forced a pass or ended the game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MoveResume {
     at: Coords,
     piece: Piece,
     flips: Vec<Coords>,
-    winner: Option<Piece>,
+    score: Score,
+    passed: Option<Piece>,
+    result: Option<GameResult>,
 }
 
 impl MoveResume {
-    fn new(at: Coords, piece: Piece, flips: Vec<Coords>, winner: Option<Piece>) -> Self {
+    fn new(at: Coords, piece: Piece, flips: Vec<Coords>, board: &Board) -> Self {
         Self {
             at,
             piece,
             flips,
-            winner,
+            score: board.score(),
+            passed: None,
+            result: None,
         }
     }
+
+    pub fn at(&self) -> Coords {
+        self.at
+    }
+
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    pub fn flips(&self) -> &[Coords] {
+        &self.flips
+    }
+
+    /// Disc counts on the board right after the move.
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// Discs of `piece` on the board after the move.
+    pub fn discs(&self, piece: Piece) -> usize {
+        self.score.get(piece)
+    }
+
+    /// The side forced to pass after this move, if any.
+    pub fn passed(&self) -> Option<Piece> {
+        self.passed
+    }
+
+    /// Set only when this move ended the game.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    pub fn winner(&self) -> Option<Piece> {
+        self.result.and_then(|r| r.winner)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+impl Display for MoveResume {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {} flipped {} ({})",
+            self.piece,
+            self.at,
+            self.flips.len(),
+            self.score
+        )?;
+        if let Some(passed) = self.passed {
+            write!(f, ", {} passes", passed)?;
+        }
+        if let Some(result) = self.result {
+            match result.winner {
+                Some(winner) => write!(f, ", {} wins", winner)?,
+                None => write!(f, ", draw")?,
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Game {
@@ -32,54 +144,510 @@ impl Game {
             turn: Piece::rand(),
             board: Board::new(board_size)?,
             moves: vec![],
+            undone: vec![],
+            result: None,
         })
     }
 
+    /// A game starting from `board` with `turn` to move and no placed pieces
+    /// behind it. A side to move without a legal move passes straight away,
+    /// and a position where neither side can move is already over.
+    pub fn from_position(board: Board, turn: Piece) -> Self {
+        let mut game = Game {
+            turn,
+            board,
+            moves: vec![],
+            undone: vec![],
+            result: None,
+        };
+        if game.legal_moves().is_empty() {
+            if !game.board.legal_moves(!turn).is_empty() {
+                game.moves.push(Move::pass(turn));
+                game.turn = !turn;
+            } else {
+                game.result = Some(GameResult::new(&game.board));
+            }
+        }
+        game
+    }
+
+    /// Legal moves for the side to move.
+    pub fn legal_moves(&self) -> Vec<LegalMove> {
+        self.board.legal_moves(self.turn)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
     pub fn place(&mut self, coords: &str) -> Result<MoveResume> {
-        let coords = Coords::from_str(coords)?;
-        let result = self
-            .board
-            .get(coords)?
-            .place(self.turn)
-            .map(|r| MoveResume::new(coords, self.turn, r, None))?;
-        self.moves.push(Move::new(self.turn, coords));
-        self.turn = !self.turn;
-        Ok(result)
+        self.play(Coords::from_str(coords)?)
+    }
+
+    /// Like [`Game::place`], with `coords` written in `notation`.
+    pub fn place_in(&mut self, coords: &str, notation: Notation) -> Result<MoveResume> {
+        self.play(notation.parse(coords)?)
+    }
+
+    /// Plays every move of a transcript such as `f5d6c3d3c4`, stopping at the
+    /// first illegal one. Passes are left out of transcripts since the game
+    /// records them on its own.
+    ///
+    /// Published games in [`Notation::Standard`] always open with Black, so
+    /// replaying one from the initial position fails unless Red is to move,
+    /// as in a game from [`Game::from_position`] with [`Piece::Red`].
+    pub fn play_transcript(
+        &mut self,
+        transcript: &str,
+        notation: Notation,
+    ) -> Result<Vec<MoveResume>> {
+        let coords = notation.parse_transcript(transcript)?;
+        if notation == Notation::Standard
+            && self.turn != Piece::Red
+            && self.moves.is_empty()
+            && self.is_initial()
+        {
+            return Err(GameError::RedOpens(self.turn).into());
+        }
+        coords.into_iter().map(|coords| self.play(coords)).collect()
+    }
+
+    fn is_initial(&self) -> bool {
+        Board::new(self.board.size()).is_ok_and(|initial| initial == self.board)
+    }
+
+    /// The pieces placed so far, written in `notation`.
+    pub fn transcript(&self, notation: Notation) -> String {
+        let placed = self
+            .moves
+            .iter()
+            .filter_map(|m| m.coords)
+            .collect::<Vec<_>>();
+        notation.transcript(&placed)
+    }
+
+    /// Places a piece for the side to move, then hands the turn over. When the
+    /// opponent has no legal move a pass is recorded and the turn stays; when
+    /// neither side can move the game ends.
+    pub fn play(&mut self, coords: Coords) -> Result<MoveResume> {
+        let resume = self.apply(coords)?;
+        self.undone.clear();
+        Ok(resume)
+    }
+
+    fn apply(&mut self, coords: Coords) -> Result<MoveResume> {
+        if self.is_over() {
+            return Err(GameError::GameOver.into());
+        }
+        let piece = self.turn;
+        let flips = self.board.get(coords)?.place(piece)?;
+        self.moves.push(Move::new(piece, coords, flips.clone()));
+        let mut resume = MoveResume::new(coords, piece, flips, &self.board);
+
+        if !self.board.legal_moves(!piece).is_empty() {
+            self.turn = !piece;
+        } else if !self.board.legal_moves(piece).is_empty() {
+            self.moves.push(Move::pass(!piece));
+            resume.passed = Some(!piece);
+        } else {
+            self.turn = !piece;
+            self.result = Some(GameResult::new(&self.board));
+            resume.result = self.result;
+        }
+
+        Ok(resume)
+    }
+
+    /// Every move played so far, including passes, oldest first.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Number of pieces placed so far. Passes do not count as plies.
+    pub fn ply(&self) -> usize {
+        self.moves.iter().filter(|m| m.coords.is_some()).count()
+    }
+
+    /// Takes back the last placed piece, together with any pass that was
+    /// recorded after it, restoring the flipped discs and the side to move.
+    /// A pass recorded before the first piece stays, as it belongs to the
+    /// starting position.
+    pub fn undo(&mut self) -> Result<Option<Move>> {
+        let Some(placed) = self.moves.iter().rposition(|m| m.coords.is_some()) else {
+            return Ok(None);
+        };
+        self.moves.truncate(placed + 1);
+        let last = self.moves.pop().expect("a placed move");
+        let coords = last.coords.expect("the last placed move");
+
+        self.board.get(coords)?.remove()?;
+        for flip in &last.flips {
+            self.board.get(*flip)?.flip()?;
+        }
+        self.turn = last.piece;
+        self.result = None;
+        self.undone.push(coords);
+
+        Ok(Some(last))
+    }
+
+    /// Replays the last move taken back with [`Game::undo`].
+    pub fn redo(&mut self) -> Result<Option<MoveResume>> {
+        match self.undone.pop() {
+            Some(coords) => self.apply(coords).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Undoes or redoes moves until exactly `ply` pieces have been placed.
+    pub fn go_to(&mut self, ply: usize) -> Result<()> {
+        let plies = self.ply() + self.undone.len();
+        if ply > plies {
+            return Err(GameError::InvalidPly(ply, plies).into());
+        }
+        while self.ply() > ply {
+            self.undo()?;
+        }
+        while self.ply() < ply {
+            self.redo()?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Move {
     piece: Piece,
-    coords: Coords,
+    coords: Option<Coords>,
+    flips: Vec<Coords>,
 }
 
 impl Move {
-    fn new(piece: Piece, coords: Coords) -> Self {
-        Self { piece, coords }
+    fn new(piece: Piece, coords: Coords, flips: Vec<Coords>) -> Self {
+        Self {
+            piece,
+            coords: Some(coords),
+            flips,
+        }
+    }
+
+    fn pass(piece: Piece) -> Self {
+        Self {
+            piece,
+            coords: None,
+            flips: vec![],
+        }
+    }
+
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    /// Where the piece was placed, `None` for a pass.
+    pub fn coords(&self) -> Option<Coords> {
+        self.coords
+    }
+
+    pub fn flips(&self) -> &[Coords] {
+        &self.flips
+    }
+
+    pub fn is_pass(&self) -> bool {
+        self.coords.is_none()
     }
 }
 
 impl Display for Move {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} at {}", self.piece, self.coords)
+        match self.coords {
+            Some(coords) => write!(f, "{} at {}", self.piece, coords),
+            None => write!(f, "{} passes", self.piece),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Game;
+    use std::str::FromStr;
+
+    use super::{Game, GameResult};
+    use crate::{
+        board::{Board, Score},
+        coordinates::{Coords, Notation},
+        piece::Piece,
+    };
+
+    #[test]
+    fn test_place_alternates_turns() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+
+        game.place("E:3").unwrap();
+        assert_eq!(game.turn, Piece::Red);
+        game.place("F:3").unwrap();
+        assert_eq!(game.turn, Piece::Blue);
+        assert_eq!(game.moves.len(), 2);
+    }
+
+    #[test]
+    fn test_legal_moves_follow_turn() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Red;
+
+        let moves = game.legal_moves();
+
+        assert_eq!(moves, game.board.legal_moves(Piece::Red));
+        assert!(moves.iter().all(|m| m.flips.len() == 1));
+    }
+
+    #[test]
+    fn test_place_rejects_illegal_moves() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+        let board = game.board.to_string();
+
+        assert!(game.place("A:1").is_err());
+        assert!(game.place("D:4").is_err());
+        assert_eq!(game.turn, Piece::Blue);
+        assert_eq!(game.board.to_string(), board);
+        assert!(game.moves.is_empty());
+    }
+
+    #[test]
+    fn test_play_standard_transcript() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Red;
+
+        let resumes = game
+            .play_transcript("f5d6c3d3c4", Notation::Standard)
+            .unwrap();
+        game.place_in("f4", Notation::Standard).unwrap();
+
+        assert_eq!(resumes.len(), 5);
+        assert_eq!(resumes[0].at(), Coords::new(4, 5));
+        assert_eq!(game.transcript(Notation::Standard), "f5d6c3d3c4f4");
+        assert!(game.transcript(Notation::RowCol).starts_with("E:6 F:4 C:3"));
+        assert!(game.play_transcript("a1", Notation::Standard).is_err());
+        assert!(game.play_transcript("f5 zz", Notation::Standard).is_err());
+    }
+
+    #[test]
+    fn test_standard_transcript_opens_with_red() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+
+        assert!(game.play_transcript("f5d6", Notation::Standard).is_err());
+        assert_eq!(game.turn, Piece::Blue);
+        assert!(game.moves().is_empty());
+
+        let mut game = Game::from_position(Board::new(8).unwrap(), Piece::Red);
+        let resumes = game.play_transcript("f5d6", Notation::Standard).unwrap();
+
+        assert_eq!(resumes[0].piece(), Piece::Red);
+        assert_eq!(resumes[1].piece(), Piece::Blue);
+        assert_eq!(game.turn, Piece::Red);
+    }
+
+    fn play_all(game: &mut Game, moves: &[&str]) {
+        for m in moves {
+            game.place(m).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_forced_pass_keeps_turn() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        play_all(
+            &mut game,
+            &["C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2"],
+        );
+
+        let resume = game.place("A:1").unwrap();
+
+        assert_eq!(resume.passed, Some(Piece::Red));
+        assert!(resume.result.is_none());
+        assert_eq!(game.turn, Piece::Blue);
+        assert_eq!(game.moves.last().unwrap().to_string(), "Red passes");
+        assert!(!game.is_over());
+    }
+
+    #[test]
+    fn test_game_over_when_nobody_can_move() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        play_all(
+            &mut game,
+            &["B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3"],
+        );
+
+        let resume = game.place("F:3").unwrap();
+
+        assert_eq!(resume.score(), Score::new(0, 13, 23));
+        let result = GameResult {
+            winner: Some(Piece::Red),
+            blue: 0,
+            red: 36,
+        };
+        assert_eq!(resume.result, Some(result));
+        assert_eq!(game.result(), Some(result));
+        assert!(game.is_over());
+        assert!(game.place("A:1").is_err());
+    }
+
+    #[test]
+    fn test_undo_restores_board_and_turn() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+        let mut snapshots = vec![(game.board.to_string(), game.turn)];
+        for m in ["E:3", "F:3", "G:3", "C:4"] {
+            game.place(m).unwrap();
+            snapshots.push((game.board.to_string(), game.turn));
+        }
+
+        while let Some((board, turn)) = snapshots.pop() {
+            assert_eq!(game.board.to_string(), board);
+            assert_eq!(game.turn, turn);
+            game.undo().unwrap();
+        }
+        assert_eq!(game.ply(), 0);
+        assert!(game.undo().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_redo_replays_undone_moves() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+        play_all(&mut game, &["E:3", "F:3", "G:3"]);
+        let board = game.board.to_string();
+
+        game.undo().unwrap();
+        game.undo().unwrap();
+        let resume = game.redo().unwrap().unwrap();
+        assert_eq!(resume.at.to_string(), "F:3");
+        game.redo().unwrap();
+
+        assert_eq!(game.board.to_string(), board);
+        assert_eq!(game.turn, Piece::Red);
+        assert!(game.redo().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_play_discards_redo_history() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+        play_all(&mut game, &["E:3", "F:3"]);
+
+        game.undo().unwrap();
+        game.place("F:5").unwrap();
+
+        assert!(game.redo().unwrap().is_none());
+        assert_eq!(game.ply(), 2);
+    }
 
     #[test]
-    fn x() {
+    fn test_undo_drops_pass_and_game_over() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        play_all(
+            &mut game,
+            &[
+                "B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3", "F:3",
+            ],
+        );
+        assert!(game.is_over());
+
+        game.undo().unwrap();
+        assert!(!game.is_over());
+        assert_eq!(game.turn, Piece::Red);
+
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        play_all(
+            &mut game,
+            &["C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2", "A:1"],
+        );
+        game.undo().unwrap();
+        assert_eq!(game.turn, Piece::Blue);
+        assert_eq!(game.moves.len(), 7);
+    }
+
+    #[test]
+    fn test_undo_keeps_opening_pass() {
+        let mut cells = vec![None; 36];
+        cells[0] = Some(Piece::Red);
+        cells[1] = Some(Piece::Blue);
+        let mut game = Game::from_position(Board::from_cells(6, &cells).unwrap(), Piece::Blue);
+        assert_eq!(game.turn, Piece::Red);
+
+        game.place("A:3").unwrap();
+        assert!(game.is_over());
+
+        assert!(game.undo().unwrap().is_some());
+        assert!(!game.is_over());
+        assert_eq!(game.turn, Piece::Red);
+        assert!(game.undo().unwrap().is_none());
+        assert_eq!(game.moves().len(), 1);
+        assert!(game.moves()[0].is_pass());
+        assert_eq!(game.turn, Piece::Red);
+    }
+
+    #[test]
+    fn test_go_to() {
         let mut game = Game::new(8).unwrap();
-        game.place("A:1");
-        game.place("A:3");
-        game.place("D:8").unwrap();
-        game.place("B:4").unwrap();
-        game.place("C:4").unwrap();
-        game.place("F:4").unwrap();
-
-        print!("{}", game.board);
+        game.turn = Piece::Blue;
+        let start = game.board.to_string();
+        play_all(&mut game, &["E:3", "F:3", "G:3", "C:4"]);
+        let end = game.board.to_string();
+
+        game.go_to(0).unwrap();
+        assert_eq!(game.board.to_string(), start);
+        assert_eq!(game.turn, Piece::Blue);
+
+        game.go_to(4).unwrap();
+        assert_eq!(game.board.to_string(), end);
+        assert!(game.go_to(5).is_err());
+    }
+
+    #[test]
+    fn test_move_resume_accessors() {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+
+        let resume = game.place("E:3").unwrap();
+
+        assert_eq!(resume.at(), Coords::from_str("E:3").unwrap());
+        assert_eq!(resume.piece(), Piece::Blue);
+        assert_eq!(resume.flips(), &[Coords::from_str("E:4").unwrap()]);
+        assert_eq!(resume.discs(Piece::Blue), 4);
+        assert_eq!(resume.discs(Piece::Red), 1);
+        assert_eq!(resume.passed(), None);
+        assert_eq!(resume.winner(), None);
+        assert!(!resume.is_game_over());
+        assert_eq!(resume.to_string(), "Blue at E:3 flipped 1 (Blue 4 - Red 1)");
+        assert_eq!(resume.clone(), resume);
+    }
+
+    #[test]
+    fn test_moves_history() {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        play_all(
+            &mut game,
+            &["C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2", "A:1"],
+        );
+
+        let last = game.moves().last().unwrap();
+        assert!(last.is_pass());
+        assert_eq!(last.piece(), Piece::Red);
+        assert_eq!(last.coords(), None);
+
+        let first = &game.moves()[0];
+        assert_eq!(first.coords(), Some(Coords::from_str("C:2").unwrap()));
+        assert_eq!(first.flips().len(), 1);
     }
 }
@@ -0,0 +1,540 @@
+//! The Generic Game Format used by online Othello servers:
+//!
+//! ```text
+//! (;GM[Othello]PB[bob]PW[ann]TY[8]BO[8 -------- -------- -------- ---O*---
+//! ---*O--- -------- -------- -------- *]B[d3//0.01]W[c5/-1.50/1.40];)
+//! ```
+//!
+//! Black is Red and White is Blue. `BO` holds the starting position, `*` for
+//! Black, `O` for White, and the side to move. Moves are in standard notation,
+//! `pa` for a pass, optionally followed by an evaluation and the time taken.
+
+use crate::{
+    board::{Board, MAX_SIZE},
+    coordinates::{Coords, Notation},
+    game::Game,
+    grid::Grid,
+    piece::Piece,
+};
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+const BLACK: char = '*';
+const WHITE: char = 'O';
+const EMPTY: char = '-';
+const PASS: &str = "pa";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GgfError {
+    #[error("a game must be enclosed in '(;' and ';)'")]
+    Unterminated,
+
+    #[error("expected a property such as GM[Othello], found '{0}'")]
+    InvalidProperty(String),
+
+    #[error("the BO property is missing")]
+    MissingBoard,
+
+    #[error("invalid board: {0}")]
+    InvalidBoard(String),
+
+    #[error("move {0}: '{1}' is neither a move nor a pass")]
+    InvalidMove(usize, String),
+
+    #[error("move {0}: {1}")]
+    IllegalMove(usize, String),
+
+    #[error("move {0}: it is not {1}'s turn")]
+    WrongSide(usize, Piece),
+
+    #[error("move {0}: {1} has legal moves and can't pass")]
+    UnexpectedPass(usize, Piece),
+
+    #[error("move {0}: {1} has no legal move, a pass was expected")]
+    MissingPass(usize, Piece),
+}
+
+/// A move as written in GGF, with what the player reported about it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GgfMove {
+    pub piece: Piece,
+    /// `None` for a pass.
+    pub coords: Option<Coords>,
+    /// The player's evaluation of the position, in discs.
+    pub eval: Option<f64>,
+    /// Seconds spent on the move.
+    pub time: Option<f64>,
+}
+
+impl GgfMove {
+    pub fn new(piece: Piece, coords: Option<Coords>) -> Self {
+        Self {
+            piece,
+            coords,
+            eval: None,
+            time: None,
+        }
+    }
+}
+
+/// A single game in GGF.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ggf {
+    /// Every property but the board and the moves, in the order read.
+    pub tags: Vec<(String, String)>,
+    pub board: Board,
+    pub to_move: Piece,
+    pub moves: Vec<GgfMove>,
+}
+
+impl Ggf {
+    /// The moves of `game` from the position it started in, with unknown
+    /// players.
+    pub fn from_game(game: &Game) -> Self {
+        // Unplay every move on a copy of the board to find where it started.
+        let board = game.board.clone();
+        for m in game.moves().iter().rev() {
+            if let Some(coords) = m.coords() {
+                let position = board.get(coords).expect("a played move");
+                position.remove().expect("an occupied square");
+                for flip in m.flips() {
+                    board
+                        .get(*flip)
+                        .expect("a flipped disc")
+                        .flip()
+                        .expect("a disc");
+                }
+            }
+        }
+
+        let mut tags = vec![
+            ("GM".to_string(), "Othello".to_string()),
+            ("PB".to_string(), "?".to_string()),
+            ("PW".to_string(), "?".to_string()),
+            ("TY".to_string(), board.size().to_string()),
+        ];
+        if let Some(result) = game.result() {
+            let diff = result.red as f64 - result.blue as f64;
+            tags.push(("RE".to_string(), format!("{:+.3}", diff)));
+        }
+
+        Self {
+            tags,
+            board,
+            to_move: game.moves().first().map_or(game.turn, |m| m.piece()),
+            moves: game
+                .moves()
+                .iter()
+                .map(|m| GgfMove::new(m.piece(), m.coords()))
+                .collect(),
+        }
+    }
+
+    pub fn with_players(mut self, blue: &str, red: &str) -> Self {
+        self.set_tag("PW", blue);
+        self.set_tag("PB", red);
+        self
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Every game in `text`, as GGF files hold one game after another.
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, GgfError> {
+        let mut games = vec![];
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let end = rest.find(";)").ok_or(GgfError::Unterminated)? + 2;
+            games.push(Self::from_str(&rest[..end])?);
+            rest = rest[end..].trim_start();
+        }
+        Ok(games)
+    }
+
+    /// Plays the moves through the rules from the starting position. Games
+    /// may stop anywhere, since they also end on time or by resignation.
+    pub fn to_game(&self) -> Result<Game, GgfError> {
+        // A starting position where the side to move cannot play comes with
+        // its pass already recorded, to be matched against the game's own.
+        let mut game = Game::from_position(self.board.clone(), self.to_move);
+        for (i, m) in self.moves.iter().enumerate() {
+            let number = i + 1;
+            if let Some(recorded) = game.moves().get(i) {
+                if m.coords.is_some() {
+                    return Err(GgfError::MissingPass(number, recorded.piece()));
+                }
+                if m.piece != recorded.piece() {
+                    return Err(GgfError::WrongSide(number, m.piece));
+                }
+                continue;
+            }
+            if m.piece != game.turn {
+                return Err(GgfError::WrongSide(number, m.piece));
+            }
+            match m.coords {
+                Some(coords) => {
+                    game.play(coords)
+                        .map_err(|e| GgfError::IllegalMove(number, e.to_string()))?;
+                }
+                None => return Err(GgfError::UnexpectedPass(number, m.piece)),
+            }
+        }
+        Ok(game)
+    }
+}
+
+fn side(piece: Piece) -> char {
+    match piece {
+        Piece::Red => BLACK,
+        Piece::Blue => WHITE,
+    }
+}
+
+fn parse_board(value: &str) -> Result<(Board, Piece), GgfError> {
+    let invalid = || GgfError::InvalidBoard(value.to_string());
+    let mut tokens = value.split_whitespace().collect::<Vec<_>>();
+    if tokens.len() < 3 {
+        return Err(invalid());
+    }
+    let size = match tokens.remove(0).parse() {
+        Ok(n) if n <= MAX_SIZE => n,
+        _ => return Err(invalid()),
+    };
+    let to_move = match tokens.pop() {
+        Some("*") => Piece::Red,
+        Some("O") => Piece::Blue,
+        _ => return Err(invalid()),
+    };
+    let cells = tokens
+        .concat()
+        .chars()
+        .map(|c| match c {
+            EMPTY => Ok(None),
+            BLACK => Ok(Some(Piece::Red)),
+            WHITE => Ok(Some(Piece::Blue)),
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let board =
+        Board::from_cells(size, &cells).map_err(|e| GgfError::InvalidBoard(e.to_string()))?;
+    Ok((board, to_move))
+}
+
+fn parse_move(number: usize, piece: Piece, value: &str) -> Result<GgfMove, GgfError> {
+    let invalid = || GgfError::InvalidMove(number, value.to_string());
+    let mut parts = value.split('/');
+    let coords = match parts.next().unwrap_or_default().trim() {
+        m if m.eq_ignore_ascii_case(PASS) => None,
+        m => Some(Notation::Standard.parse(m).map_err(|_| invalid())?),
+    };
+    let annotation = |s: Option<&str>, parse: fn(&str) -> Option<f64>| match s.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(s) => parse(s).map(Some).ok_or_else(invalid),
+    };
+    let eval = annotation(parts.next(), |s| s.parse().ok())?;
+    let time = annotation(parts.next(), parse_time)?;
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(GgfMove {
+        piece,
+        coords,
+        eval,
+        time,
+    })
+}
+
+/// Seconds written either plainly or as a clock: `1.5`, `02:01`, `1:00:00`.
+fn parse_time(s: &str) -> Option<f64> {
+    s.split(':').try_fold(0.0, |total, part| {
+        part.parse::<f64>().ok().map(|n| total * 60.0 + n)
+    })
+}
+
+impl FromStr for Ggf {
+    type Err = GgfError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s
+            .trim()
+            .strip_prefix("(;")
+            .and_then(|s| s.strip_suffix(";)"))
+            .ok_or(GgfError::Unterminated)?;
+
+        let mut tags = vec![];
+        let mut position = None;
+        let mut moves = vec![];
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            let invalid = || GgfError::InvalidProperty(rest.chars().take(20).collect());
+            let open = rest.find('[').ok_or_else(invalid)?;
+            let close = rest.find(']').ok_or_else(invalid)?;
+            let name = &rest[..open];
+            if close < open || name.is_empty() || !name.chars().all(|c| c.is_ascii_uppercase()) {
+                return Err(invalid());
+            }
+            let value = &rest[open + 1..close];
+            match name {
+                "BO" => position = Some(parse_board(value)?),
+                "B" => moves.push(parse_move(moves.len() + 1, Piece::Red, value)?),
+                "W" => moves.push(parse_move(moves.len() + 1, Piece::Blue, value)?),
+                _ => tags.push((name.to_string(), value.to_string())),
+            }
+            rest = &rest[close + 1..];
+        }
+
+        let (board, to_move) = position.ok_or(GgfError::MissingBoard)?;
+        Ok(Self {
+            tags,
+            board,
+            to_move,
+            moves,
+        })
+    }
+}
+
+impl Display for GgfMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.piece {
+            Piece::Red => "B",
+            Piece::Blue => "W",
+        };
+        let coords = self
+            .coords
+            .map_or(PASS.to_string(), |c| Notation::Standard.format(c));
+        write!(f, "{}[{}", name, coords)?;
+        if self.eval.is_some() || self.time.is_some() {
+            let eval = self.eval.map_or(String::new(), |e| e.to_string());
+            let time = self.time.map_or(String::new(), |t| t.to_string());
+            write!(f, "/{}/{}", eval, time)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl Display for Ggf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(;")?;
+        for (name, value) in &self.tags {
+            write!(f, "{}[{}]", name, value)?;
+        }
+
+        let size = self.board.size();
+        write!(f, "BO[{}", size)?;
+        for row in 0..size {
+            let cells = (0..size)
+                .map(|col| Grid::piece(&self.board, Coords::new(row, col)).map_or(EMPTY, side))
+                .collect::<String>();
+            write!(f, " {}", cells)?;
+        }
+        write!(f, " {}]", side(self.to_move))?;
+
+        for m in &self.moves {
+            write!(f, "{}", m)?;
+        }
+        write!(f, ";)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = "(;GM[Othello]PC[NIOS]DT[2003.12.15_13:24:03.MST]PB[bob]PW[ann]\
+        TY[8]RE[+0.000]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- \
+        -------- -------- *]B[d3//0.01]W[c5/-1.5/1.4]B[f6]W[f5//2:01];)";
+
+    fn game(moves: &[&str]) -> Game {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in moves {
+            game.place(m).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_read() {
+        let ggf = Ggf::from_str(GAME).unwrap();
+
+        assert_eq!(ggf.tag("PB"), Some("bob"));
+        assert_eq!(ggf.tag("PW"), Some("ann"));
+        assert_eq!(ggf.board, Board::new(8).unwrap());
+        assert_eq!(ggf.to_move, Piece::Red);
+        assert_eq!(ggf.moves.len(), 4);
+        assert_eq!(ggf.moves[0].time, Some(0.01));
+        assert_eq!(ggf.moves[1].eval, Some(-1.5));
+        assert_eq!(ggf.moves[3].time, Some(121.0));
+
+        let game = ggf.to_game().unwrap();
+        assert_eq!(game.transcript(Notation::Standard), "d3c5f6f5");
+        assert_eq!(game.turn, Piece::Red);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let wipeout = game(&[
+            "B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3", "F:3",
+        ]);
+        let mut passes = game(&["C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2", "A:1"]);
+        let next = passes.legal_moves()[0].coords;
+        passes.play(next).unwrap();
+
+        for game in [wipeout, passes] {
+            let ggf = Ggf::from_game(&game).with_players("ann", "bob");
+            let text = ggf.to_string();
+            let read = Ggf::from_str(&text).unwrap();
+
+            assert_eq!(read, ggf);
+            assert_eq!(read.board, Board::new(6).unwrap());
+            let replayed = read.to_game().unwrap();
+            assert_eq!(replayed.moves(), game.moves());
+            assert_eq!(replayed.board, game.board);
+            assert_eq!(replayed.result(), game.result());
+        }
+
+        let wipeout = Ggf::from_game(&game(&[
+            "B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3", "F:3",
+        ]));
+        assert_eq!(wipeout.tag("RE"), Some("+36.000"));
+
+        let ggf = Ggf::from_str(GAME).unwrap();
+        assert_eq!(Ggf::from_str(&ggf.to_string()).unwrap(), ggf);
+        assert_eq!(
+            Ggf::parse_all(&format!("{}\n{}\n", GAME, GAME))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_passes() {
+        let text = Ggf::from_game(&game(&[
+            "C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2", "A:1",
+        ]))
+        .to_string();
+
+        assert!(text.ends_with("W[a1]B[pa];)"));
+        assert_eq!(
+            Ggf::from_str(&text.replace("B[pa]", ""))
+                .unwrap()
+                .to_game()
+                .unwrap()
+                .moves()
+                .len(),
+            9
+        );
+        assert_eq!(
+            Ggf::from_str(&text.replace("B[pa]", "B[b6]"))
+                .unwrap()
+                .to_game()
+                .err(),
+            Some(GgfError::MissingPass(9, Piece::Red))
+        );
+        assert_eq!(
+            Ggf::from_str(&GAME.replace("W[c5/-1.5/1.4]", "W[PA]"))
+                .unwrap()
+                .to_game()
+                .err(),
+            Some(GgfError::UnexpectedPass(2, Piece::Blue))
+        );
+    }
+
+    #[test]
+    fn test_starting_position_without_moves() {
+        let read = |board: &str, moves: &str| {
+            Ggf::from_str(&format!("(;GM[Othello]BO[6 {}]{};)", board, moves))
+                .unwrap()
+                .to_game()
+        };
+        let rows = "------ ------ ------ ------ ------";
+
+        let game = read(&format!("*O---- {} O", rows), "").unwrap();
+        assert!(game.moves()[0].is_pass());
+        assert_eq!(game.turn, Piece::Red);
+        assert!(!game.is_over());
+
+        assert_eq!(
+            read(&format!("*O---- {} O", rows), "W[c1]").err(),
+            Some(GgfError::MissingPass(1, Piece::Blue))
+        );
+        assert_eq!(
+            read(&format!("*O---- {} O", rows), "B[PA]").err(),
+            Some(GgfError::WrongSide(1, Piece::Red))
+        );
+
+        let game = read(&format!("*O---- {} O", rows), "W[PA]B[c1]").unwrap();
+        assert_eq!(game.moves().len(), 2);
+        assert_eq!(game.result().unwrap().winner, Some(Piece::Red));
+
+        let game = read(&format!("**---- {} O", rows), "").unwrap();
+        assert!(game.is_over());
+        assert_eq!(game.result().unwrap().red, 36);
+        assert!(game.moves().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_games() {
+        let read = |from: &str, to: &str| Ggf::from_str(&GAME.replace(from, to));
+
+        assert_eq!(read(";)", ""), Err(GgfError::Unterminated));
+        assert_eq!(
+            read("PC[NIOS]", "PC NIOS"),
+            Err(GgfError::InvalidProperty(
+                "PC NIOSDT[2003.12.15".to_string()
+            ))
+        );
+        assert_eq!(
+            read(
+                "BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]",
+                ""
+            ),
+            Err(GgfError::MissingBoard)
+        );
+        assert!(matches!(
+            read("---O*---", "---X*---"),
+            Err(GgfError::InvalidBoard(_))
+        ));
+        assert!(matches!(
+            read("---O*---", "---O*--"),
+            Err(GgfError::InvalidBoard(_))
+        ));
+        for size in ["100000", "4294967296"] {
+            assert!(matches!(
+                read("BO[8 ", &format!("BO[{} ", size)),
+                Err(GgfError::InvalidBoard(_))
+            ));
+        }
+        assert_eq!(
+            read("B[f6]", "B[z0]"),
+            Err(GgfError::InvalidMove(3, "z0".to_string()))
+        );
+        assert_eq!(
+            read("W[c5/-1.5/1.4]", "W[c5/bad/1.4]"),
+            Err(GgfError::InvalidMove(2, "c5/bad/1.4".to_string()))
+        );
+        assert_eq!(
+            read("B[f6]", "W[f6]").unwrap().to_game().err(),
+            Some(GgfError::WrongSide(3, Piece::Blue))
+        );
+        assert!(matches!(
+            read("B[f6]", "B[a1]").unwrap().to_game().err(),
+            Some(GgfError::IllegalMove(3, _))
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use crate::{
+    board::{BoardError, LegalMove, Score},
+    coordinates::Coords,
+    piece::Piece,
+    stability, zobrist,
+};
+
+/// Operations shared by every board representation, so rules, players and
+/// search can work on the generic [`Board`](crate::board::Board) as well as on
+/// the 8x8 [`BitBoard`](crate::bitboard::BitBoard).
+pub trait Grid {
+    fn size(&self) -> usize;
+
+    /// The piece at `coords`, `None` when empty or off the board.
+    fn piece(&self, coords: Coords) -> Option<Piece>;
+
+    /// Squares `piece` would flip by playing at `coords`, without playing.
+    fn flips(&self, coords: Coords, piece: Piece) -> Vec<Coords>;
+
+    /// Every square where `piece` could legally play, in row-major order.
+    fn legal_moves(&self, piece: Piece) -> Vec<LegalMove>;
+
+    fn has_legal_move(&self, piece: Piece) -> bool {
+        !self.legal_moves(piece).is_empty()
+    }
+
+    /// Plays `piece` at `coords`, returning the flipped squares. Illegal moves
+    /// leave the grid untouched.
+    fn place(&mut self, coords: Coords, piece: Piece) -> Result<Vec<Coords>, BoardError>;
+
+    fn score(&self) -> Score;
+
+    /// Zobrist hash of the position with `to_move` to play. Equal positions
+    /// hash the same whatever their representation.
+    fn hash(&self, to_move: Piece) -> u64 {
+        zobrist::hash(self, to_move)
+    }
+
+    /// Discs of `piece` that can never be flipped again.
+    fn stable(&self, piece: Piece) -> HashSet<Coords> {
+        stability::stable(self, piece)
+    }
+}
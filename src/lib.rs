@@ -2,12 +2,25 @@ use std::ops::{Deref, Not};
 
 use enum_iterator::Sequence;
 
+pub mod ai;
+pub mod bitboard;
 pub mod board;
+pub mod console;
 pub mod coordinates;
+pub mod fen;
 pub mod game;
+pub mod ggf;
+pub mod grid;
 pub mod piece;
+pub mod player;
 pub mod position;
+pub mod record;
+pub mod stability;
+pub mod symmetry;
+pub mod tui;
 pub mod walker;
+pub mod wthor;
+pub mod zobrist;
 pub struct Wrap<T>(pub T);
 
 impl<T> Deref for Wrap<T> {
@@ -1,3 +1,39 @@
-fn main() {
-    println!("Hello, world!");
+use anyhow::bail;
+use reversi::{
+    ai::book::{Book, DEFAULT_DEPTH},
+    console::Console,
+    game::Game,
+    tui::Tui,
+};
+use std::{env, fs, io};
+
+fn main() -> anyhow::Result<()> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    match args.first().map(String::as_str) {
+        Some("--tui") => {
+            let size = match args.get(1) {
+                Some(size) => size.parse()?,
+                None => 8,
+            };
+            Tui::new(Game::new(size)?).run()
+        }
+        Some("--build-book") => {
+            let (games, book) = match (args.get(1), args.get(2)) {
+                (Some(games), Some(book)) => (games, book),
+                _ => bail!("usage: reversi --build-book GAMES BOOK [SIZE]"),
+            };
+            let size = match args.get(3) {
+                Some(size) => size.parse()?,
+                None => 8,
+            };
+            let built = Book::from_transcripts(size, &fs::read_to_string(games)?, DEFAULT_DEPTH)?;
+            built.save(book)?;
+            println!("{} lines written to {}", built.len(), book);
+            Ok(())
+        }
+        _ => Console::new(io::stdin().lock(), io::stdout())
+            .clear_screen(true)
+            .run(),
+    }
 }
@@ -4,7 +4,7 @@ use rand::Rng;
 
 use crate::{board::EMPTY_POSITION, Wrap};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Piece {
     Blue,
     Red,
@@ -36,7 +36,12 @@ impl Not for Piece {
 
 impl Piece {
     pub fn rand() -> Self {
-        if rand::thread_rng().gen_bool(0.5) {
+        Self::rand_with(&mut rand::thread_rng())
+    }
+
+    /// Like [`Piece::rand`], drawing from the given generator.
+    pub fn rand_with<R: Rng>(rng: &mut R) -> Self {
+        if rng.gen_bool(0.5) {
             Piece::Blue
         } else {
             Piece::Red
@@ -83,6 +88,21 @@ mod tests {
         assert!(Piece::rand() == Piece::Blue || p == Piece::Red)
     }
 
+    #[test]
+    fn test_rand_with_seed() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let sequence = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..32)
+                .map(|_| Piece::rand_with(&mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sequence(3), sequence(3));
+        assert_ne!(sequence(3), sequence(4));
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(Piece::Red.to_string(), "Red");
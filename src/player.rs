@@ -0,0 +1,258 @@
+use crate::{
+    coordinates::Coords,
+    game::{Game, GameResult, MoveResume},
+    piece::Piece,
+};
+use anyhow::Result;
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Write},
+    str::FromStr,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PlayerError {
+    #[error("{0} passed while having legal moves")]
+    IllegalPass(Piece),
+
+    #[error("{0} has no scripted move left")]
+    OutOfMoves(String),
+
+    #[error("{0} closed its input")]
+    InputClosed(String),
+}
+
+/// What a player decides to do on its turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Place(Coords),
+    Pass,
+}
+
+/// Someone or something choosing moves for one side of a [`Game`].
+pub trait Player {
+    fn name(&self) -> &str;
+
+    /// Picks a move for `game.turn`. The game is never over when asked.
+    fn play(&mut self, game: &Game) -> Result<Action>;
+}
+
+impl<P: Player + ?Sized> Player for Box<P> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn play(&mut self, game: &Game) -> Result<Action> {
+        (**self).play(game)
+    }
+}
+
+/// Plays a fixed list of actions, in order.
+pub struct ScriptedPlayer {
+    name: String,
+    actions: VecDeque<Action>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(name: &str, actions: Vec<Action>) -> Self {
+        Self {
+            name: name.to_string(),
+            actions: actions.into(),
+        }
+    }
+
+    /// Parses moves such as `D:3`; `pass` stands for a pass.
+    pub fn parse(name: &str, moves: &[&str]) -> Result<Self> {
+        let actions = moves
+            .iter()
+            .map(|m| match *m {
+                "pass" => Ok(Action::Pass),
+                m => Ok(Action::Place(Coords::from_str(m)?)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(name, actions))
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, _: &Game) -> Result<Action> {
+        self.actions
+            .pop_front()
+            .ok_or_else(|| PlayerError::OutOfMoves(self.name.clone()).into())
+    }
+}
+
+/// A human typing moves such as `D:3`, asked again until the move is legal.
+pub struct ConsolePlayer<R: BufRead, W: Write> {
+    name: String,
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> ConsolePlayer<R, W> {
+    pub fn new(name: &str, input: R, output: W) -> Self {
+        Self {
+            name: name.to_string(),
+            input,
+            output,
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Player for ConsolePlayer<R, W> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, game: &Game) -> Result<Action> {
+        let legal = game.legal_moves();
+        loop {
+            write!(self.output, "{} ({}) to move: ", self.name, game.turn)?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Err(PlayerError::InputClosed(self.name.clone()).into());
+            }
+
+            match line.trim() {
+                "pass" if legal.is_empty() => return Ok(Action::Pass),
+                "pass" => writeln!(self.output, "You have legal moves, you can't pass.")?,
+                s => match Coords::from_str(s) {
+                    Ok(coords) if legal.iter().any(|m| m.coords == coords) => {
+                        return Ok(Action::Place(coords))
+                    }
+                    Ok(coords) => writeln!(self.output, "{} is not a legal move.", coords)?,
+                    Err(e) => writeln!(self.output, "{}", e)?,
+                },
+            }
+        }
+    }
+}
+
+/// Alternates two players on a game until it ends.
+pub struct Runner<B: Player, R: Player> {
+    blue: B,
+    red: R,
+}
+
+impl<B: Player, R: Player> Runner<B, R> {
+    pub fn new(blue: B, red: R) -> Self {
+        Self { blue, red }
+    }
+
+    pub fn player(&self, piece: Piece) -> &dyn Player {
+        match piece {
+            Piece::Blue => &self.blue,
+            Piece::Red => &self.red,
+        }
+    }
+
+    /// Asks the side to move for one action and applies it. Returns `None`
+    /// when the game is already over.
+    pub fn step(&mut self, game: &mut Game) -> Result<Option<MoveResume>> {
+        if game.is_over() {
+            return Ok(None);
+        }
+        let action = match game.turn {
+            Piece::Blue => self.blue.play(game)?,
+            Piece::Red => self.red.play(game)?,
+        };
+        match action {
+            Action::Place(coords) => game.play(coords).map(Some),
+            Action::Pass => Err(PlayerError::IllegalPass(game.turn).into()),
+        }
+    }
+
+    /// Plays until neither side can move.
+    pub fn run(&mut self, game: &mut Game) -> Result<GameResult> {
+        while self.step(game)?.is_some() {}
+        Ok(game.result().expect("the game is over"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::ai::random::RandomPlayer;
+
+    fn game() -> Game {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        game
+    }
+
+    #[test]
+    fn test_scripted_players_finish_game() {
+        let red = ScriptedPlayer::parse("red", &["B:3", "E:5", "B:1", "B:5", "F:3"]).unwrap();
+        let blue = ScriptedPlayer::parse("blue", &["B:4", "C:2", "B:2", "E:3"]).unwrap();
+        let mut game = game();
+
+        let result = Runner::new(blue, red).run(&mut game).unwrap();
+
+        assert_eq!(result.winner, Some(Piece::Red));
+        assert_eq!(game.ply(), 9);
+    }
+
+    #[test]
+    fn test_scripted_player_out_of_moves() {
+        let red = ScriptedPlayer::parse("red", &["B:3"]).unwrap();
+        let blue = ScriptedPlayer::parse("blue", &[]).unwrap();
+        let mut game = game();
+        let mut runner = Runner::new(blue, red);
+
+        runner.step(&mut game).unwrap();
+        let err = runner.step(&mut game).unwrap_err();
+
+        assert_eq!(
+            err.downcast::<PlayerError>().unwrap(),
+            PlayerError::OutOfMoves("blue".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pass_with_legal_moves_is_rejected() {
+        let red = ScriptedPlayer::parse("red", &["pass"]).unwrap();
+        let blue = ScriptedPlayer::parse("blue", &[]).unwrap();
+        let mut game = game();
+
+        let err = Runner::new(blue, red).step(&mut game).unwrap_err();
+
+        assert_eq!(
+            err.downcast::<PlayerError>().unwrap(),
+            PlayerError::IllegalPass(Piece::Red)
+        );
+    }
+
+    #[test]
+    fn test_console_player_asks_until_legal() {
+        let mut output = vec![];
+        let mut player = ConsolePlayer::new("ann", Cursor::new("x\nA:1\npass\nB:3\n"), &mut output);
+
+        let action = player.play(&game()).unwrap();
+
+        assert_eq!(action, Action::Place(Coords::new(1, 2)));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Invalid coordinates format: 'x'"));
+        assert!(output.contains("A:1 is not a legal move."));
+        assert!(output.contains("you can't pass"));
+    }
+
+    #[test]
+    fn test_random_players_finish_game() {
+        let mut game = Game::new(8).unwrap();
+        let mut runner = Runner::new(RandomPlayer::seeded(1), RandomPlayer::seeded(2));
+
+        let result = runner.run(&mut game).unwrap();
+
+        assert!(game.is_over());
+        assert_eq!(result.blue + result.red, 64);
+        assert_eq!(runner.player(Piece::Blue).name(), "random");
+    }
+}
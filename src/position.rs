@@ -1,5 +1,5 @@
 use crate::{
-    board::MatrixPointer,
+    board::{MatrixPointer, EMPTY_POSITION},
     coordinates::Coords,
     piece::Piece,
     walker::{Walkable, Walker, WalkerIterator},
@@ -15,8 +15,14 @@ pub enum PositionError {
     #[error("position could not be flipped")]
     FlipError,
 
-    #[error("position could not be flipped")]
+    #[error("position is already occupied")]
     PositionAlreadyOccupied,
+
+    #[error("position is empty")]
+    PositionEmpty,
+
+    #[error("{1} cannot play at {0}: no disc would be flipped")]
+    IllegalMove(Coords, Piece),
 }
 
 #[derive(Clone)]
@@ -72,7 +78,7 @@ impl Position {
         Position { matrix, coords }
     }
 
-    fn piece(&self) -> Option<Piece> {
+    pub fn piece(&self) -> Option<Piece> {
         let c = self.matrix.borrow().read(self.coords);
         let piece: Wrap<Option<Piece>> = c.into();
         *piece
@@ -82,16 +88,29 @@ impl Position {
         if self.occupied() {
             return Err(PositionError::PositionAlreadyOccupied);
         }
-        let flipped = self.solve(piece);
+        let flippables = self.solve(piece);
+        if flippables.is_empty() {
+            return Err(PositionError::IllegalMove(self.coords, piece));
+        }
         self.matrix.borrow_mut().write(self.coords, piece.into());
-        Ok(flipped.iter().map(|p| p.coords).collect())
+        flippables
+            .into_iter()
+            .map(|p| p.flip().map(|p| p.coords))
+            .collect()
+    }
+
+    /// Squares that would be flipped if `piece` were placed here, without
+    /// touching the board. Empty when the move is not legal.
+    pub fn flips(&self, piece: Piece) -> Vec<Coords> {
+        if self.occupied() {
+            return vec![];
+        }
+        self.solve(piece).iter().map(|p| p.coords).collect()
     }
 
     fn solve(&self, piece: Piece) -> Vec<Position> {
         all::<Dir>()
-            .into_iter()
             .flat_map(|dir| self.solve_dir(piece, dir))
-            .map(|p| p.flip().unwrap())
             .collect::<Vec<_>>()
     }
 
@@ -122,6 +141,17 @@ impl Position {
         }
     }
 
+    /// Takes the piece off this position, returning what was there.
+    pub fn remove(self) -> Result<Piece, PositionError> {
+        match self.piece() {
+            Some(p) => {
+                self.matrix.borrow_mut().write(self.coords, EMPTY_POSITION);
+                Ok(p)
+            }
+            None => Err(PositionError::PositionEmpty),
+        }
+    }
+
     fn occupied(&self) -> bool {
         self.piece().is_some()
     }
@@ -153,7 +183,7 @@ mod tests {
 
     use std::{cell::RefCell, rc::Rc, str::FromStr};
 
-    use crate::{board::Matrix, piece, walker::Walkable};
+    use crate::{board::Matrix, walker::Walkable};
 
     use super::*;
 
@@ -191,4 +221,81 @@ mod tests {
             dbg!("None");
         }
     }
+
+    fn matrix() -> MatrixPointer {
+        let mut raw = Matrix::new(8);
+        raw.write(Coords::from_str("D:4").unwrap(), Piece::Blue.into());
+        raw.write(Coords::from_str("E:5").unwrap(), Piece::Blue.into());
+        raw.write(Coords::from_str("D:5").unwrap(), Piece::Red.into());
+        raw.write(Coords::from_str("E:4").unwrap(), Piece::Red.into());
+        Rc::new(RefCell::new(raw))
+    }
+
+    #[test]
+    fn test_place_flips_outflanked_discs() {
+        let matrix = matrix();
+        let at = Coords::from_str("E:3").unwrap();
+
+        let flips = Position::new(matrix.clone(), at)
+            .place(Piece::Blue)
+            .unwrap();
+
+        assert_eq!(flips, vec![Coords::from_str("E:4").unwrap()]);
+        assert_eq!(Position::new(matrix.clone(), at).piece(), Some(Piece::Blue));
+        assert_eq!(
+            Position::new(matrix, Coords::from_str("E:4").unwrap()).piece(),
+            Some(Piece::Blue)
+        );
+    }
+
+    #[test]
+    fn test_place_rejects_non_capturing_move() {
+        let matrix = matrix();
+        let at = Coords::from_str("A:1").unwrap();
+        let before = matrix.borrow().to_vec();
+
+        assert_eq!(
+            Position::new(matrix.clone(), at).place(Piece::Blue),
+            Err(PositionError::IllegalMove(at, Piece::Blue))
+        );
+        assert_eq!(matrix.borrow().to_vec(), before);
+    }
+
+    #[test]
+    fn test_flips_does_not_mutate() {
+        let matrix = matrix();
+        let before = matrix.borrow().to_vec();
+        let position = Position::new(matrix.clone(), Coords::from_str("E:3").unwrap());
+
+        assert_eq!(
+            position.flips(Piece::Blue),
+            vec![Coords::from_str("E:4").unwrap()]
+        );
+        assert!(position.flips(Piece::Red).is_empty());
+        assert_eq!(matrix.borrow().to_vec(), before);
+    }
+
+    #[test]
+    fn test_remove() {
+        let matrix = matrix();
+        let at = Coords::from_str("D:4").unwrap();
+
+        assert_eq!(Position::new(matrix.clone(), at).remove(), Ok(Piece::Blue));
+        assert_eq!(Position::new(matrix.clone(), at).piece(), None);
+        assert_eq!(
+            Position::new(matrix, at).remove(),
+            Err(PositionError::PositionEmpty)
+        );
+    }
+
+    #[test]
+    fn test_place_rejects_occupied_position() {
+        let matrix = matrix();
+        let at = Coords::from_str("D:4").unwrap();
+
+        assert_eq!(
+            Position::new(matrix, at).place(Piece::Red),
+            Err(PositionError::PositionAlreadyOccupied)
+        );
+    }
 }
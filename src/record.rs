@@ -0,0 +1,438 @@
+//! Game records: a header of tags followed by the moves, e.g.
+//!
+//! ```text
+//! [Size "6"]
+//! [First "Red"]
+//! [Blue "ann"]
+//! [Red "bob"]
+//! [Date "2023-04-01"]
+//! [Result "Blue 0 - Red 36"]
+//!
+//! B:3 B:4 E:5 C:2 B:1 B:2 B:5 E:3 F:3
+//! ```
+//!
+//! Passes are written `pass`, and an unfinished game has `*` for a result.
+//! A `"` or `\` in a tag value is escaped with a `\`.
+
+use crate::{
+    board::MAX_SIZE,
+    coordinates::Coords,
+    game::{Game, GameResult},
+    piece::Piece,
+};
+use anyhow::Result;
+use regex::Regex;
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+use thiserror::Error;
+
+/// Moves written on each line of a record.
+const MOVES_PER_LINE: usize = 10;
+
+const UNKNOWN: &str = "?";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RecordError {
+    #[error("line {0}: expected a tag such as [Size \"8\"], found '{1}'")]
+    InvalidTag(usize, String),
+
+    #[error("the {0} tag is missing")]
+    MissingTag(&'static str),
+
+    #[error("invalid {0}: '{1}'")]
+    InvalidValue(&'static str, String),
+
+    #[error("move {0}: '{1}' is neither a move nor a pass")]
+    InvalidMove(usize, String),
+
+    #[error("move {0}: {1}")]
+    IllegalMove(usize, String),
+
+    #[error("move {0}: {1} has legal moves and can't pass")]
+    UnexpectedPass(usize, Piece),
+
+    #[error("move {0}: {1} has no legal move, a pass was expected")]
+    MissingPass(usize, Piece),
+
+    #[error("the record says {0} but the moves end in {1}")]
+    ResultMismatch(String, String),
+}
+
+/// A finished or unfinished game as written to a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub size: usize,
+    pub first: Piece,
+    pub blue: String,
+    pub red: String,
+    pub date: String,
+    pub result: Option<GameResult>,
+    /// Every move in order, `None` for a pass.
+    pub moves: Vec<Option<Coords>>,
+}
+
+impl Record {
+    /// The record of `game` so far, with unknown players and date.
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            size: game.board.size(),
+            first: game.moves().first().map_or(game.turn, |m| m.piece()),
+            blue: UNKNOWN.to_string(),
+            red: UNKNOWN.to_string(),
+            date: UNKNOWN.to_string(),
+            result: game.result(),
+            moves: game.moves().iter().map(|m| m.coords()).collect(),
+        }
+    }
+
+    pub fn with_players(mut self, blue: &str, red: &str) -> Self {
+        self.blue = blue.to_string();
+        self.red = red.to_string();
+        self
+    }
+
+    pub fn with_date(mut self, date: &str) -> Self {
+        self.date = date.to_string();
+        self
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// Plays the moves through the rules, checking every pass is forced and
+    /// the result matches the final position.
+    pub fn replay(&self) -> Result<Game, RecordError> {
+        let invalid_size = || RecordError::InvalidValue("Size", self.size.to_string());
+        if self.size > MAX_SIZE {
+            return Err(invalid_size());
+        }
+        let mut game = Game::new(self.size).map_err(|_| invalid_size())?;
+        game.turn = self.first;
+
+        for (i, m) in self.moves.iter().enumerate() {
+            let number = i + 1;
+            let recorded = game.moves().len();
+            match m {
+                Some(_) if recorded > i => {
+                    return Err(RecordError::MissingPass(number, game.moves()[i].piece()));
+                }
+                Some(coords) => {
+                    game.play(*coords)
+                        .map_err(|e| RecordError::IllegalMove(number, e.to_string()))?;
+                }
+                None if recorded > i => {}
+                None if game.is_over() => {
+                    return Err(RecordError::IllegalMove(
+                        number,
+                        "the game is over".to_string(),
+                    ));
+                }
+                None => return Err(RecordError::UnexpectedPass(number, game.turn)),
+            }
+        }
+        if let Some(pass) = game.moves().get(self.moves.len()) {
+            return Err(RecordError::MissingPass(self.moves.len() + 1, pass.piece()));
+        }
+
+        if game.result() != self.result {
+            return Err(RecordError::ResultMismatch(
+                result_to_string(self.result),
+                result_to_string(game.result()),
+            ));
+        }
+        Ok(game)
+    }
+}
+
+fn result_to_string(result: Option<GameResult>) -> String {
+    match result {
+        Some(result) => format!("Blue {} - Red {}", result.blue, result.red),
+        None => "*".to_string(),
+    }
+}
+
+fn parse_result(s: &str) -> Result<Option<GameResult>, RecordError> {
+    if s == "*" {
+        return Ok(None);
+    }
+    let invalid = || RecordError::InvalidValue("Result", s.to_string());
+    let regex = Regex::new(r"\ABlue (\d+) - Red (\d+)\z").expect("Invalid regex");
+    let captures = regex.captures(s).ok_or_else(invalid)?;
+    let blue: usize = captures[1].parse().map_err(|_| invalid())?;
+    let red: usize = captures[2].parse().map_err(|_| invalid())?;
+    let winner = match blue.cmp(&red) {
+        std::cmp::Ordering::Greater => Some(Piece::Blue),
+        std::cmp::Ordering::Less => Some(Piece::Red),
+        std::cmp::Ordering::Equal => None,
+    };
+    Ok(Some(GameResult { winner, blue, red }))
+}
+
+fn parse_piece(s: &str) -> Result<Piece, RecordError> {
+    match s {
+        "Blue" => Ok(Piece::Blue),
+        "Red" => Ok(Piece::Red),
+        _ => Err(RecordError::InvalidValue("First", s.to_string())),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn write_tag(f: &mut std::fmt::Formatter<'_>, name: &str, value: &str) -> std::fmt::Result {
+    writeln!(f, "[{} \"{}\"]", name, escape(value))
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_tag(f, "Size", &self.size.to_string())?;
+        write_tag(f, "First", &self.first.to_string())?;
+        write_tag(f, "Blue", &self.blue)?;
+        write_tag(f, "Red", &self.red)?;
+        write_tag(f, "Date", &self.date)?;
+        write_tag(f, "Result", &result_to_string(self.result))?;
+        writeln!(f)?;
+        for line in self.moves.chunks(MOVES_PER_LINE) {
+            let line = line
+                .iter()
+                .map(|m| m.map_or("pass".to_string(), |c| c.to_string()))
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Record {
+    type Err = RecordError;
+
+    /// Reads the tags and moves of a record, without checking the moves are
+    /// legal: see [`Record::replay`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = Regex::new(r#"\A\[(\w+) "((?:[^"\\]|\\.)*)"\]\z"#).expect("Invalid regex");
+        let mut tags = vec![];
+        let mut lines = s.lines().enumerate().map(|(i, l)| (i + 1, l.trim()));
+        for (n, line) in lines.by_ref() {
+            if line.is_empty() {
+                if tags.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            let captures = tag
+                .captures(line)
+                .ok_or_else(|| RecordError::InvalidTag(n, line.to_string()))?;
+            tags.push((captures[1].to_string(), unescape(&captures[2])));
+        }
+        let value = |name: &'static str| {
+            tags.iter()
+                .find(|(t, _)| t == name)
+                .map(|(_, v)| v.clone())
+                .ok_or(RecordError::MissingTag(name))
+        };
+
+        let size = value("Size")?;
+        let size = match size.parse() {
+            Ok(n) if n <= MAX_SIZE => n,
+            _ => return Err(RecordError::InvalidValue("Size", size)),
+        };
+        let moves = lines
+            .flat_map(|(_, line)| line.split_whitespace())
+            .enumerate()
+            .map(|(i, m)| match m {
+                "pass" => Ok(None),
+                m => Coords::from_str(m)
+                    .map(Some)
+                    .map_err(|_| RecordError::InvalidMove(i + 1, m.to_string())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            size,
+            first: parse_piece(&value("First")?)?,
+            blue: value("Blue").unwrap_or_else(|_| UNKNOWN.to_string()),
+            red: value("Red").unwrap_or_else(|_| UNKNOWN.to_string()),
+            date: value("Date").unwrap_or_else(|_| UNKNOWN.to_string()),
+            result: parse_result(&value("Result")?)?,
+            moves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIPEOUT: &str = "\
+[Size \"6\"]
+[First \"Red\"]
+[Blue \"ann\"]
+[Red \"bob\"]
+[Date \"2023-04-01\"]
+[Result \"Blue 0 - Red 36\"]
+
+B:3 B:4 E:5 C:2 B:1 B:2 B:5 E:3 F:3
+";
+
+    fn game(moves: &[&str]) -> Game {
+        let mut game = Game::new(6).unwrap();
+        game.turn = Piece::Red;
+        for m in moves {
+            game.place(m).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let game = game(&[
+            "B:3", "B:4", "E:5", "C:2", "B:1", "B:2", "B:5", "E:3", "F:3",
+        ]);
+        let record = Record::from_game(&game)
+            .with_players("ann", "bob")
+            .with_date("2023-04-01");
+
+        assert_eq!(record.to_string(), WIPEOUT);
+        assert_eq!(Record::from_str(WIPEOUT).unwrap(), record);
+
+        let replayed = record.replay().unwrap();
+        assert_eq!(replayed.board, game.board);
+        assert_eq!(replayed.moves(), game.moves());
+    }
+
+    #[test]
+    fn test_quoted_tags() {
+        let record = Record::from_str(WIPEOUT)
+            .unwrap()
+            .with_players("ann \"the ace\"", "C:\\bob\\");
+        let text = record.to_string();
+
+        assert!(text.contains(r#"[Blue "ann \"the ace\""]"#));
+        assert!(text.contains(r#"[Red "C:\\bob\\"]"#));
+        assert_eq!(Record::from_str(&text).unwrap(), record);
+    }
+
+    #[test]
+    fn test_passes_and_unfinished_games() {
+        let mut game = game(&["C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2", "A:1"]);
+        let next = game.legal_moves()[0].coords;
+        game.play(next).unwrap();
+        let record = Record::from_game(&game);
+        let text = record.to_string();
+
+        assert!(text.contains("[Result \"*\"]"));
+        assert!(text.contains(&format!("A:1 pass {}", next)));
+        assert_eq!(
+            Record::from_str(&text).unwrap().replay().unwrap().moves(),
+            game.moves()
+        );
+    }
+
+    #[test]
+    fn test_corrupt_records() {
+        let corrupt = |from: &str, to: &str| Record::from_str(&WIPEOUT.replace(from, to));
+
+        assert_eq!(
+            corrupt("[Date \"2023-04-01\"]", "Date: today"),
+            Err(RecordError::InvalidTag(5, "Date: today".to_string()))
+        );
+        assert_eq!(
+            corrupt("[Size \"6\"]\n", ""),
+            Err(RecordError::MissingTag("Size"))
+        );
+        assert_eq!(
+            corrupt("\"Red\"]\n[Blue", "\"Green\"]\n[Blue"),
+            Err(RecordError::InvalidValue("First", "Green".to_string()))
+        );
+        assert_eq!(
+            corrupt("[Size \"6\"]", "[Size \"100000\"]"),
+            Err(RecordError::InvalidValue("Size", "100000".to_string()))
+        );
+        assert_eq!(
+            corrupt("[Size \"6\"]", "[Size \"18446744073709551615\"]"),
+            Err(RecordError::InvalidValue(
+                "Size",
+                "18446744073709551615".to_string()
+            ))
+        );
+        let mut huge = Record::from_str(WIPEOUT).unwrap();
+        huge.size = 100_000;
+        assert_eq!(
+            huge.replay().err(),
+            Some(RecordError::InvalidValue("Size", "100000".to_string()))
+        );
+        assert_eq!(
+            corrupt("E:5", "E5"),
+            Err(RecordError::InvalidMove(3, "E5".to_string()))
+        );
+        assert_eq!(
+            corrupt("E:5", "A:0"),
+            Err(RecordError::InvalidMove(3, "A:0".to_string()))
+        );
+        assert_eq!(
+            corrupt("E:5", "A:99999999999999999999999"),
+            Err(RecordError::InvalidMove(
+                3,
+                "A:99999999999999999999999".to_string()
+            ))
+        );
+        assert_eq!(
+            corrupt("Blue 0 - Red 36", "Blue 99999999999999999999999 - Red 0"),
+            Err(RecordError::InvalidValue(
+                "Result",
+                "Blue 99999999999999999999999 - Red 0".to_string()
+            ))
+        );
+        assert_eq!(
+            corrupt("E:5", "A:1").unwrap().replay().err(),
+            Some(RecordError::IllegalMove(
+                3,
+                "Red cannot play at A:1: no disc would be flipped".to_string()
+            ))
+        );
+        assert_eq!(
+            corrupt("B:4", "pass").unwrap().replay().err(),
+            Some(RecordError::UnexpectedPass(2, Piece::Blue))
+        );
+        assert_eq!(
+            corrupt(" F:3", "").unwrap().replay().err(),
+            Some(RecordError::ResultMismatch(
+                "Blue 0 - Red 36".to_string(),
+                "*".to_string()
+            ))
+        );
+        assert_eq!(
+            corrupt("F:3", "F:3 pass").unwrap().replay().err(),
+            Some(RecordError::IllegalMove(10, "the game is over".to_string()))
+        );
+
+        let passes = Record::from_game(&game(&[
+            "C:2", "B:2", "A:2", "C:1", "E:5", "A:3", "D:2", "A:1",
+        ]));
+        let text = passes.to_string().replace(" pass", "");
+        assert_eq!(
+            Record::from_str(&format!("{}E:1", text))
+                .unwrap()
+                .replay()
+                .err(),
+            Some(RecordError::MissingPass(9, Piece::Red))
+        );
+    }
+}
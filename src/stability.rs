@@ -0,0 +1,178 @@
+//! Stable discs: discs that no sequence of moves can flip again.
+//!
+//! A disc can only be flipped along one of the four lines through it, so it is
+//! stable once every line is safe. A line is safe when it is full, so nobody
+//! can play on it, or when the disc is anchored on one side of it by the edge
+//! of the board or by a stable disc of its own colour. Anchors spread from the
+//! corners, so discs are marked until nothing changes.
+
+use std::collections::HashSet;
+
+use crate::{
+    coordinates::Coords,
+    grid::Grid,
+    piece::Piece,
+    walker::{Walkable, Walker},
+    Dir,
+};
+use enum_iterator::all;
+
+/// Every stable disc of `piece`.
+pub fn stable<G: Grid + ?Sized>(grid: &G, piece: Piece) -> HashSet<Coords> {
+    let size = grid.size();
+    let inside = move |c: &Coords| c.row < size && c.col < size;
+    // Up, UpRight, Right and DownRight along with their opposites.
+    let lines = all::<Dir>().take(4).collect::<Vec<_>>();
+
+    let full = |c: Coords, dir: Dir| {
+        [dir, !dir].into_iter().all(|d| {
+            c.walker(d)
+                .into_iter()
+                .take_while(inside)
+                .all(|n| grid.piece(n).is_some())
+        })
+    };
+    let discs = (0..size * size)
+        .map(|index| Coords::new(index / size, index % size))
+        .filter(|c| grid.piece(*c) == Some(piece))
+        .map(|c| {
+            let open = lines
+                .iter()
+                .copied()
+                .filter(|dir| !full(c, *dir))
+                .collect::<Vec<_>>();
+            (c, open)
+        })
+        .collect::<Vec<_>>();
+
+    let mut stable = HashSet::new();
+    loop {
+        let anchored = |c: Coords, dir: Dir, stable: &HashSet<Coords>| match c
+            .walker(dir)
+            .walk_one()
+            .filter(inside)
+        {
+            Some(n) => stable.contains(&n),
+            None => true,
+        };
+        let found = discs
+            .iter()
+            .filter(|(c, _)| !stable.contains(c))
+            .filter(|(c, open)| {
+                open.iter()
+                    .all(|dir| anchored(*c, *dir, &stable) || anchored(*c, !*dir, &stable))
+            })
+            .map(|(c, _)| *c)
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            return stable;
+        }
+        stable.extend(found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+    use crate::board::Board;
+
+    fn board(size: usize, blue: &[(usize, usize)], red: &[(usize, usize)]) -> Board {
+        let mut cells = vec![None; size * size];
+        for (row, col) in blue {
+            cells[row * size + col] = Some(Piece::Blue);
+        }
+        for (row, col) in red {
+            cells[row * size + col] = Some(Piece::Red);
+        }
+        Board::from_cells(size, &cells).unwrap()
+    }
+
+    fn set(coords: &[(usize, usize)]) -> HashSet<Coords> {
+        coords.iter().map(|(r, c)| Coords::new(*r, *c)).collect()
+    }
+
+    #[test]
+    fn test_initial_position_has_no_stable_disc() {
+        let board = Board::new(8).unwrap();
+
+        assert!(board.stable(Piece::Blue).is_empty());
+        assert!(board.stable(Piece::Red).is_empty());
+    }
+
+    #[test]
+    fn test_edge_anchored() {
+        let board = board(
+            6,
+            &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (2, 2)],
+            &[(0, 3), (5, 0)],
+        );
+
+        assert_eq!(
+            board.stable(Piece::Blue),
+            set(&[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)])
+        );
+        assert_eq!(board.stable(Piece::Red), set(&[(5, 0)]));
+    }
+
+    #[test]
+    fn test_full_lines() {
+        // Row C, column 3 and both diagonals through C:3 are full, so its
+        // Blue disc can't be flipped although nothing anchors it.
+        let size = 6;
+        let mut red = vec![];
+        for i in 0..size {
+            red.extend([(2, i), (i, 2), (i, i)]);
+            if i <= 4 {
+                red.push((i, 4 - i));
+            }
+        }
+        red.retain(|c| *c != (2, 2));
+        red.sort();
+        red.dedup();
+        let board = board(size, &[(2, 2)], &red);
+
+        assert_eq!(board.stable(Piece::Blue), set(&[(2, 2)]));
+    }
+
+    #[test]
+    fn test_full_board_is_stable() {
+        let cells = (0..36)
+            .map(|i| Some(if i % 3 == 0 { Piece::Blue } else { Piece::Red }))
+            .collect::<Vec<_>>();
+        let board = Board::from_cells(6, &cells).unwrap();
+
+        assert_eq!(board.stable(Piece::Blue).len(), 12);
+        assert_eq!(board.stable(Piece::Red).len(), 24);
+    }
+
+    #[test]
+    fn test_stable_discs_never_flip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..10 {
+            let mut board = Board::new(6).unwrap();
+            let mut piece = Piece::Red;
+            let mut stable: Vec<(Coords, Piece)> = vec![];
+
+            loop {
+                for (c, owner) in &stable {
+                    assert_eq!(Grid::piece(&board, *c), Some(*owner), "{} flipped", c);
+                    assert!(board.stable(*owner).contains(c));
+                }
+                stable = [Piece::Blue, Piece::Red]
+                    .into_iter()
+                    .flat_map(|p| board.stable(p).into_iter().map(move |c| (c, p)))
+                    .collect();
+
+                let moves = Grid::legal_moves(&board, piece);
+                if let Some(m) = moves.choose(&mut rng) {
+                    Grid::place(&mut board, m.coords, piece).unwrap();
+                } else if !board.has_legal_move(!piece) {
+                    break;
+                }
+                piece = !piece;
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! The eight symmetries of a square board: rotations by quarter turns and
+//! reflections. Positions that are images of each other play the same, which
+//! opening books and position caches use to share what they know.
+
+use crate::{
+    board::{Board, BoardError},
+    coordinates::Coords,
+    grid::Grid,
+    piece::Piece,
+    Dir,
+};
+use enum_iterator::{all, Sequence};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Sequence)]
+pub enum Symmetry {
+    Identity,
+    /// A quarter turn clockwise.
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors left and right.
+    FlipHorizontal,
+    /// Mirrors top and bottom.
+    FlipVertical,
+    /// Mirrors along the diagonal from `A:1`, swapping rows and columns.
+    FlipDiagonal,
+    /// Mirrors along the other diagonal.
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    /// Where `coords` lands on a board of `size`.
+    pub fn coords(self, size: usize, coords: Coords) -> Coords {
+        let last = size - 1;
+        let Coords { row, col } = coords;
+        match self {
+            Symmetry::Identity => Coords::new(row, col),
+            Symmetry::Rotate90 => Coords::new(col, last - row),
+            Symmetry::Rotate180 => Coords::new(last - row, last - col),
+            Symmetry::Rotate270 => Coords::new(last - col, row),
+            Symmetry::FlipHorizontal => Coords::new(row, last - col),
+            Symmetry::FlipVertical => Coords::new(last - row, col),
+            Symmetry::FlipDiagonal => Coords::new(col, row),
+            Symmetry::FlipAntiDiagonal => Coords::new(last - col, last - row),
+        }
+    }
+
+    /// Where a step towards `dir` points once transformed.
+    pub fn dir(self, dir: Dir) -> Dir {
+        // Directions go clockwise in eighths of a turn: rotations add to
+        // them, reflections subtract them from the direction of their axis.
+        let index = all::<Dir>()
+            .position(|d| d == dir)
+            .expect("every direction") as isize;
+        let index = match self {
+            Symmetry::Identity => index,
+            Symmetry::Rotate90 => index + 2,
+            Symmetry::Rotate180 => index + 4,
+            Symmetry::Rotate270 => index + 6,
+            Symmetry::FlipHorizontal => -index,
+            Symmetry::FlipVertical => 4 - index,
+            Symmetry::FlipDiagonal => 6 - index,
+            Symmetry::FlipAntiDiagonal => 2 - index,
+        };
+        all::<Dir>()
+            .nth(index.rem_euclid(8) as usize)
+            .expect("eight directions")
+    }
+
+    /// The symmetry undoing this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// A copy of `board` with every disc moved to its image.
+    pub fn board(self, board: &Board) -> Board {
+        let size = board.size();
+        let mut cells = vec![None; size * size];
+        for row in 0..size {
+            for col in 0..size {
+                let to = self.coords(size, Coords::new(row, col));
+                cells[to.row * size + to.col] = Grid::piece(board, Coords::new(row, col));
+            }
+        }
+        Board::from_cells(size, &cells).expect("the size is unchanged")
+    }
+
+    /// Whether the initial position is its own image, so the same openings
+    /// can be played on both sides of the symmetry.
+    pub fn keeps_initial_position(self, size: usize) -> Result<bool, BoardError> {
+        let initial = Board::new(size)?;
+        Ok(self.board(&initial) == initial)
+    }
+}
+
+/// The image of `board` that comes first when comparing squares in row-major
+/// order, empty before Blue before Red, along with the symmetry producing it.
+/// Every image of a position has the same canonical form.
+pub fn canonical(board: &Board) -> (Board, Symmetry) {
+    let key = |board: &Board| {
+        let size = board.size();
+        (0..size * size)
+            .map(
+                |i| match Grid::piece(board, Coords::new(i / size, i % size)) {
+                    None => 0,
+                    Some(Piece::Blue) => 1,
+                    Some(Piece::Red) => 2,
+                },
+            )
+            .collect::<Vec<u8>>()
+    };
+    all::<Symmetry>()
+        .map(|s| (s.board(board), s))
+        .min_by_key(|(image, _)| key(image))
+        .expect("there is always the identity")
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+    use crate::walker::{Walkable, Walker};
+
+    fn random_board(seed: u64, plies: usize) -> Board {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut board = Board::new(8).unwrap();
+        let mut piece = Piece::Red;
+        for _ in 0..plies {
+            let moves = Grid::legal_moves(&board, piece);
+            if let Some(m) = moves.choose(&mut rng) {
+                Grid::place(&mut board, m.coords, piece).unwrap();
+            }
+            piece = !piece;
+        }
+        board
+    }
+
+    #[test]
+    fn test_coords() {
+        let c = Coords::new(0, 1);
+
+        assert_eq!(Symmetry::Rotate90.coords(8, c), Coords::new(1, 7));
+        assert_eq!(Symmetry::Rotate180.coords(8, c), Coords::new(7, 6));
+        assert_eq!(Symmetry::Rotate270.coords(8, c), Coords::new(6, 0));
+        assert_eq!(Symmetry::FlipHorizontal.coords(8, c), Coords::new(0, 6));
+        assert_eq!(Symmetry::FlipVertical.coords(8, c), Coords::new(7, 1));
+        assert_eq!(Symmetry::FlipDiagonal.coords(8, c), Coords::new(1, 0));
+        assert_eq!(Symmetry::FlipAntiDiagonal.coords(8, c), Coords::new(6, 7));
+    }
+
+    #[test]
+    fn test_steps_follow_direction_mapping() {
+        let size = 6;
+        for symmetry in all::<Symmetry>() {
+            for dir in all::<Dir>() {
+                assert_eq!(symmetry.dir(!dir), !symmetry.dir(dir));
+                for index in 0..size * size {
+                    let from = Coords::new(index / size, index % size);
+                    let Some(to) = from.walker(dir).walk_one() else {
+                        continue;
+                    };
+                    if to.row >= size || to.col >= size {
+                        continue;
+                    }
+                    let image = symmetry.coords(size, from);
+                    assert_eq!(
+                        image.walker(symmetry.dir(dir)).walk_one(),
+                        Some(symmetry.coords(size, to)),
+                        "{:?} of {:?} from {}",
+                        symmetry,
+                        dir,
+                        from
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let board = random_board(1, 12);
+        for symmetry in all::<Symmetry>() {
+            let image = symmetry.board(&board);
+            assert_eq!(symmetry.inverse().board(&image), board);
+            assert_eq!(
+                Grid::legal_moves(&image, Piece::Red).len(),
+                Grid::legal_moves(&board, Piece::Red).len()
+            );
+        }
+        let quarter = |b: &Board| Symmetry::Rotate90.board(b);
+        assert_eq!(quarter(&quarter(&board)), Symmetry::Rotate180.board(&board));
+    }
+
+    #[test]
+    fn test_initial_position_symmetries() {
+        let kept = all::<Symmetry>()
+            .filter(|s| s.keeps_initial_position(8).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kept,
+            vec![
+                Symmetry::Identity,
+                Symmetry::Rotate180,
+                Symmetry::FlipDiagonal,
+                Symmetry::FlipAntiDiagonal
+            ]
+        );
+    }
+
+    #[test]
+    fn test_canonical() {
+        let board = random_board(2, 10);
+        let (canonical_board, symmetry) = canonical(&board);
+
+        assert_eq!(symmetry.board(&board), canonical_board);
+        for s in all::<Symmetry>() {
+            assert_eq!(canonical(&s.board(&board)).0, canonical_board);
+        }
+    }
+}
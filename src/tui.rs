@@ -0,0 +1,297 @@
+use crate::{
+    coordinates::{Coords, RowNumber},
+    game::{Game, MoveResume},
+    piece::Piece,
+};
+use anyhow::Result;
+use std::io::{self, Write};
+use termion::{
+    clear, color, cursor, event::Key, input::TermRead, raw::IntoRawMode,
+    screen::IntoAlternateScreen, style,
+};
+
+const MOVE_LIST_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Full screen game driven by a cursor moved with the arrow keys or `hjkl`.
+pub struct Tui {
+    game: Game,
+    cursor: Coords,
+    last: Option<MoveResume>,
+    message: String,
+}
+
+impl Tui {
+    pub fn new(game: Game) -> Self {
+        let half = game.board.size() / 2;
+        let message = format!("{} starts.", game.turn);
+        Self {
+            game,
+            cursor: Coords::new(half - 1, half - 1),
+            last: None,
+            message,
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn cursor(&self) -> Coords {
+        self.cursor
+    }
+
+    /// Takes over the terminal until the player quits.
+    pub fn run(mut self) -> Result<()> {
+        let mut screen = io::stdout().into_raw_mode()?.into_alternate_screen()?;
+        write!(screen, "{}", cursor::Hide)?;
+        self.render(&mut screen)?;
+
+        for key in io::stdin().keys() {
+            if self.handle(key?) == Flow::Quit {
+                break;
+            }
+            self.render(&mut screen)?;
+        }
+
+        write!(screen, "{}", cursor::Show)?;
+        screen.flush()?;
+        Ok(())
+    }
+
+    pub fn handle(&mut self, key: Key) -> Flow {
+        let last = self.game.board.size() - 1;
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return Flow::Quit,
+            Key::Up | Key::Char('k') => self.cursor.row = self.cursor.row.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.cursor.row = (self.cursor.row + 1).min(last),
+            Key::Left | Key::Char('h') => self.cursor.col = self.cursor.col.saturating_sub(1),
+            Key::Right | Key::Char('l') => self.cursor.col = (self.cursor.col + 1).min(last),
+            Key::Char('\n') | Key::Char(' ') => self.play(),
+            Key::Char('u') => self.undo(),
+            Key::Char('r') => self.redo(),
+            _ => {}
+        }
+        Flow::Continue
+    }
+
+    fn play(&mut self) {
+        match self.game.play(self.cursor) {
+            Ok(resume) => self.record(resume),
+            Err(e) => self.message = e.to_string(),
+        }
+    }
+
+    fn undo(&mut self) {
+        self.last = None;
+        self.message = match self.game.undo() {
+            Ok(Some(undone)) => format!("Took back {}.", undone),
+            Ok(None) => "Nothing to undo.".to_string(),
+            Err(e) => e.to_string(),
+        };
+    }
+
+    fn redo(&mut self) {
+        match self.game.redo() {
+            Ok(Some(resume)) => self.record(resume),
+            Ok(None) => self.message = "Nothing to redo.".to_string(),
+            Err(e) => self.message = e.to_string(),
+        }
+    }
+
+    fn record(&mut self, resume: MoveResume) {
+        self.message = match (resume.passed(), self.game.result()) {
+            (_, Some(result)) => match result.winner {
+                Some(winner) => format!(
+                    "Game over. {} wins {} to {}.",
+                    winner,
+                    result.blue.max(result.red),
+                    result.blue.min(result.red)
+                ),
+                None => format!("Game over. Draw at {}.", result.blue),
+            },
+            (Some(passed), None) => format!("{} has no legal move and passes.", passed),
+            (None, None) => format!("{} to move.", self.game.turn),
+        };
+        self.last = Some(resume);
+    }
+
+    pub fn render<W: Write>(&self, out: &mut W) -> Result<()> {
+        let size = self.game.board.size();
+        let label = RowNumber::new(size - 1).to_string().len() as u16;
+        let cell = (size.to_string().len() + 1) as u16;
+        let legal = match self.game.is_over() {
+            true => vec![],
+            false => self.game.legal_moves(),
+        };
+
+        write!(out, "{}{}", clear::All, cursor::Goto(1, 1))?;
+        write!(out, "{:w$}", "", w = label as usize)?;
+        for col in 0..size {
+            write!(out, "{:>w$}", col + 1, w = cell as usize)?;
+        }
+
+        for row in 0..size {
+            let y = row as u16 + 2;
+            write!(
+                out,
+                "{}{:>w$}",
+                cursor::Goto(1, y),
+                RowNumber::new(row).to_string(),
+                w = label as usize
+            )?;
+            for col in 0..size {
+                let coords = Coords::new(row, col);
+                let x = label + 1 + col as u16 * cell;
+                write!(out, "{}", cursor::Goto(x, y))?;
+                if coords == self.cursor {
+                    write!(out, "{}", color::Bg(color::White))?;
+                }
+                write!(out, "{:w$}", "", w = cell as usize - 1)?;
+                self.render_cell(out, coords, legal.iter().any(|m| m.coords == coords))?;
+                write!(out, "{}", style::Reset)?;
+            }
+        }
+
+        let x = label + 2 + size as u16 * cell;
+        let score = self.game.board.score();
+        let mut side = vec![
+            format!("Blue {:>3}", score.get(Piece::Blue)),
+            format!("Red  {:>3}", score.get(Piece::Red)),
+            String::new(),
+        ];
+        if !self.game.is_over() {
+            side.push(format!(
+                "{} to move, {} legal moves",
+                self.game.turn,
+                legal.len()
+            ));
+        }
+        side.push(String::new());
+        let moves = self.game.moves();
+        let skip = moves.len().saturating_sub(MOVE_LIST_LENGTH);
+        side.extend(
+            moves
+                .iter()
+                .enumerate()
+                .skip(skip)
+                .map(|(i, m)| format!("{:>3}. {}", i + 1, m)),
+        );
+        for (i, line) in side.iter().enumerate() {
+            write!(out, "{}{}", cursor::Goto(x, i as u16 + 1), line)?;
+        }
+
+        let y = size as u16 + 3;
+        write!(out, "{}{}", cursor::Goto(1, y), self.message)?;
+        write!(
+            out,
+            "{}arrows/hjkl move, enter plays, u undo, r redo, q quits",
+            cursor::Goto(1, y + 1)
+        )?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn render_cell<W: Write>(&self, out: &mut W, coords: Coords, legal: bool) -> Result<()> {
+        let position = self.game.board.get(coords)?;
+        let (placed, flipped) = match &self.last {
+            Some(last) => (last.at() == coords, last.flips().contains(&coords)),
+            None => (false, false),
+        };
+        if placed || flipped {
+            write!(out, "{}", style::Bold)?;
+        }
+        match position.piece() {
+            Some(Piece::Blue) => write!(out, "{}", color::Fg(color::Blue))?,
+            Some(Piece::Red) => write!(out, "{}", color::Fg(color::Red))?,
+            None => write!(out, "{}", color::Fg(color::LightBlack))?,
+        }
+        let glyph = match (position.piece(), legal) {
+            (Some(_), _) if placed => "◆",
+            (Some(_), _) if flipped => "◉",
+            (Some(_), _) => "●",
+            (None, true) => "·",
+            (None, false) => "○",
+        };
+        write!(out, "{}", glyph)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn tui() -> Tui {
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Blue;
+        Tui::new(game)
+    }
+
+    #[test]
+    fn test_cursor_moves_and_stays_on_board() {
+        let mut tui = tui();
+        assert_eq!(tui.cursor(), Coords::from_str("D:4").unwrap());
+
+        tui.handle(Key::Char('k'));
+        tui.handle(Key::Left);
+        assert_eq!(tui.cursor(), Coords::from_str("C:3").unwrap());
+
+        for _ in 0..10 {
+            tui.handle(Key::Up);
+            tui.handle(Key::Char('l'));
+        }
+        assert_eq!(tui.cursor(), Coords::from_str("A:8").unwrap());
+    }
+
+    #[test]
+    fn test_enter_plays_at_cursor() {
+        let mut tui = tui();
+        tui.handle(Key::Down);
+        tui.handle(Key::Left);
+
+        tui.handle(Key::Char('\n'));
+
+        let last = tui.last.as_ref().unwrap();
+        assert_eq!(last.at(), Coords::from_str("E:3").unwrap());
+        assert_eq!(last.flips(), &[Coords::from_str("E:4").unwrap()]);
+        assert_eq!(tui.game().turn, Piece::Red);
+        assert_eq!(tui.message, "Red to move.");
+    }
+
+    #[test]
+    fn test_illegal_move_is_reported() {
+        let mut tui = tui();
+
+        tui.handle(Key::Char(' '));
+
+        assert!(tui.last.is_none());
+        assert!(tui.message.contains("already occupied"));
+        assert_eq!(tui.handle(Key::Char('q')), Flow::Quit);
+    }
+
+    #[test]
+    fn test_render_shows_labels_and_moves() {
+        let mut tui = tui();
+        tui.handle(Key::Down);
+        tui.handle(Key::Left);
+        tui.handle(Key::Char('\n'));
+
+        let mut out = vec![];
+        tui.render(&mut out).unwrap();
+        let screen = String::from_utf8(out).unwrap();
+
+        assert!(screen.contains('H'));
+        assert!(screen.contains("  1. Blue at E:3"));
+        assert!(screen.contains("Red to move, 3 legal moves"));
+        assert!(screen.contains('◆'));
+        assert!(screen.contains('◉'));
+    }
+}
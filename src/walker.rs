@@ -68,11 +68,11 @@ mod tests {
         type WItem = Dummy;
 
         fn walk(&self, length: usize) -> Option<Self::WItem> {
-            return if length > 9 {
+            if length > 9 {
                 None
             } else {
                 Some(Dummy(self.0 .0 + length))
-            };
+            }
         }
     }
 
@@ -82,7 +82,7 @@ mod tests {
         type IntoIter = WalkerIterator<Self::Item>;
 
         fn into_iter(self) -> Self::IntoIter {
-            WalkerIterator::new(self.0, Dir::Up)
+            WalkerIterator::new(self.0, self.1)
         }
     }
 
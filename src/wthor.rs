@@ -0,0 +1,385 @@
+//! The WTHOR database of the French Othello Federation, where every file
+//! starts with a 16 byte header:
+//!
+//! - `.wtb` files hold 8x8 games, 68 bytes each: the tournament, Black and
+//!   White as little-endian indexes into the name files, Black's score and its
+//!   theoretical score, then the 60 moves as `10 * row + column` counted from
+//!   1, `0` once the game is over. Passes are left out.
+//! - `.jou` files hold player names, 20 bytes each.
+//! - `.trn` files hold tournament names, 26 bytes each.
+//!
+//! Black is Red and White is Blue.
+
+use crate::{coordinates::Coords, game::Game, piece::Piece};
+use anyhow::Result;
+use std::{fs, path::Path};
+use thiserror::Error;
+
+const HEADER_LEN: usize = 16;
+const GAME_LEN: usize = 68;
+const MOVES: usize = 60;
+const PLAYER_LEN: usize = 20;
+const TOURNAMENT_LEN: usize = 26;
+const SIZE: usize = 8;
+const UNKNOWN: &str = "?";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WthorError {
+    #[error("the file is {0} bytes long, {1} bytes were expected")]
+    Truncated(usize, usize),
+
+    #[error("only 8x8 games are supported, not {0}x{0}")]
+    UnsupportedSize(usize),
+
+    #[error("game {0}, move {1}: {2} is not a square")]
+    InvalidMove(usize, usize, u8),
+
+    #[error("game {0}, move {1}: {2}")]
+    IllegalMove(usize, usize, String),
+}
+
+/// The header shared by every WTHOR file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// When the file was written, as year, month and day.
+    pub created: (u16, u8, u8),
+    /// Games in a `.wtb` file.
+    pub games: u32,
+    /// Names in a `.jou` or `.trn` file.
+    pub names: u16,
+    /// The year the games were played.
+    pub year: u16,
+    pub size: usize,
+    /// Empty squares left when the theoretical scores were computed.
+    pub depth: u8,
+}
+
+impl Header {
+    pub fn read(bytes: &[u8]) -> Result<Self, WthorError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(WthorError::Truncated(bytes.len(), HEADER_LEN));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        Ok(Self {
+            created: (bytes[0] as u16 * 100 + bytes[1] as u16, bytes[2], bytes[3]),
+            games: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            names: u16_at(8),
+            year: u16_at(10),
+            // Older files leave the size at 0 for 8x8.
+            size: match bytes[12] {
+                0 => SIZE,
+                size => size as usize,
+            },
+            depth: bytes[14],
+        })
+    }
+}
+
+/// A game as stored in a `.wtb` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WthorRecord {
+    pub tournament: u16,
+    pub black: u16,
+    pub white: u16,
+    /// Black's discs at the end, empty squares going to the winner.
+    pub score: u8,
+    /// Black's discs with perfect play from `depth` empty squares on.
+    pub theoretical: u8,
+    pub moves: Vec<Coords>,
+}
+
+impl WthorRecord {
+    fn read(index: usize, bytes: &[u8]) -> Result<Self, WthorError> {
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let moves = bytes[8..8 + MOVES]
+            .iter()
+            .take_while(|m| **m != 0)
+            .enumerate()
+            .map(|(i, m)| {
+                let (row, col) = ((m / 10) as usize, (m % 10) as usize);
+                if (1..=SIZE).contains(&row) && (1..=SIZE).contains(&col) {
+                    Ok(Coords::new(row - 1, col - 1))
+                } else {
+                    Err(WthorError::InvalidMove(index, i + 1, *m))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            tournament: u16_at(0),
+            black: u16_at(2),
+            white: u16_at(4),
+            score: bytes[6],
+            theoretical: bytes[7],
+            moves,
+        })
+    }
+
+    /// Plays the moves from the initial position, Black first.
+    pub fn replay(&self, index: usize) -> Result<Game, WthorError> {
+        let mut game = Game::new(SIZE).expect("8 is a valid size");
+        game.turn = Piece::Red;
+        for (i, coords) in self.moves.iter().enumerate() {
+            game.play(*coords)
+                .map_err(|e| WthorError::IllegalMove(index, i + 1, e.to_string()))?;
+        }
+        Ok(game)
+    }
+}
+
+/// A replayed game along with who played it and where.
+pub struct WthorGame {
+    pub tournament: String,
+    pub black: String,
+    pub white: String,
+    pub year: u16,
+    pub score: u8,
+    pub theoretical: u8,
+    pub game: Game,
+}
+
+/// Player and tournament names from `.jou` and `.trn` files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Names {
+    pub players: Vec<String>,
+    pub tournaments: Vec<String>,
+}
+
+impl Names {
+    pub fn read(jou: &[u8], trn: &[u8]) -> Result<Self, WthorError> {
+        Ok(Self {
+            players: names(jou, PLAYER_LEN)?,
+            tournaments: names(trn, TOURNAMENT_LEN)?,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>, Q: AsRef<Path>>(jou: P, trn: Q) -> Result<Self> {
+        Ok(Self::read(&fs::read(jou)?, &fs::read(trn)?)?)
+    }
+
+    /// The player at `index`, `?` when the files don't know it.
+    pub fn player(&self, index: u16) -> &str {
+        self.players
+            .get(index as usize)
+            .map_or(UNKNOWN, |n| n.as_str())
+    }
+
+    pub fn tournament(&self, index: u16) -> &str {
+        self.tournaments
+            .get(index as usize)
+            .map_or(UNKNOWN, |n| n.as_str())
+    }
+}
+
+/// Names of `len` bytes each, in ISO-8859-1 and padded with zeros.
+fn names(bytes: &[u8], len: usize) -> Result<Vec<String>, WthorError> {
+    let header = Header::read(bytes)?;
+    let end = HEADER_LEN + header.names as usize * len;
+    if bytes.len() < end {
+        return Err(WthorError::Truncated(bytes.len(), end));
+    }
+    Ok(bytes[HEADER_LEN..end]
+        .chunks_exact(len)
+        .map(|name| {
+            name.iter()
+                .take_while(|b| **b != 0)
+                .map(|b| *b as char)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect())
+}
+
+/// The games of a `.wtb` file.
+pub struct Wthor {
+    pub header: Header,
+    bytes: Vec<u8>,
+}
+
+impl Wthor {
+    pub fn read(bytes: Vec<u8>) -> Result<Self, WthorError> {
+        let header = Header::read(&bytes)?;
+        if header.size != SIZE {
+            return Err(WthorError::UnsupportedSize(header.size));
+        }
+        let end = HEADER_LEN + header.games as usize * GAME_LEN;
+        if bytes.len() < end {
+            return Err(WthorError::Truncated(bytes.len(), end));
+        }
+        Ok(Self { header, bytes })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::read(fs::read(path)?)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.header.games as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every game as stored, without replaying it.
+    pub fn records(&self) -> impl Iterator<Item = Result<WthorRecord, WthorError>> + '_ {
+        self.bytes[HEADER_LEN..HEADER_LEN + self.len() * GAME_LEN]
+            .chunks_exact(GAME_LEN)
+            .enumerate()
+            .map(|(i, bytes)| WthorRecord::read(i, bytes))
+    }
+
+    /// Every game replayed, named after `names`. A game that fails to replay
+    /// yields its error and the next ones carry on.
+    pub fn games<'a>(
+        &'a self,
+        names: &'a Names,
+    ) -> impl Iterator<Item = Result<WthorGame, WthorError>> + 'a {
+        self.records().enumerate().map(|(i, record)| {
+            let record = record?;
+            Ok(WthorGame {
+                tournament: names.tournament(record.tournament).to_string(),
+                black: names.player(record.black).to_string(),
+                white: names.player(record.white).to_string(),
+                year: self.header.year,
+                score: record.score,
+                theoretical: record.theoretical,
+                game: record.replay(i)?,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use super::*;
+
+    fn header(records: usize, size: u8) -> Vec<u8> {
+        let mut bytes = vec![20, 23, 4, 1];
+        bytes.extend((records as u32).to_le_bytes());
+        bytes.extend((records as u16).to_le_bytes());
+        bytes.extend(2023u16.to_le_bytes());
+        bytes.extend([size, 0, 22, 0]);
+        bytes
+    }
+
+    fn name_file(names: &[&str], len: usize) -> Vec<u8> {
+        let mut bytes = header(names.len(), 0);
+        for name in names {
+            let mut name = name.chars().map(|c| c as u8).collect::<Vec<_>>();
+            name.resize(len, 0);
+            bytes.extend(name);
+        }
+        bytes
+    }
+
+    fn game_bytes(tournament: u16, black: u16, white: u16, game: &Game) -> Vec<u8> {
+        let mut bytes = vec![];
+        for n in [tournament, black, white] {
+            bytes.extend(n.to_le_bytes());
+        }
+        let score = game.result().map_or(0, |r| r.red as u8);
+        bytes.extend([score, score]);
+        let mut moves = game
+            .moves()
+            .iter()
+            .filter_map(|m| m.coords())
+            .map(|c| (10 * (c.row + 1) + c.col + 1) as u8)
+            .collect::<Vec<_>>();
+        moves.resize(MOVES, 0);
+        bytes.extend(moves);
+        bytes
+    }
+
+    fn random_game(seed: u64) -> Game {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut game = Game::new(8).unwrap();
+        game.turn = Piece::Red;
+        while !game.is_over() {
+            let coords = game.legal_moves().choose(&mut rng).unwrap().coords;
+            game.play(coords).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_read_games() {
+        let games = [random_game(1), random_game(2)];
+        let mut wtb = header(2, 8);
+        wtb.extend(game_bytes(1, 0, 2, &games[0]));
+        wtb.extend(game_bytes(0, 2, 1, &games[1]));
+        let wthor = Wthor::read(wtb).unwrap();
+        let names = Names::read(
+            &name_file(
+                &["Tastet Marc", "Shaman Brian", "Caspard Hervé"],
+                PLAYER_LEN,
+            ),
+            &name_file(&["Paris", "Championnat du Monde"], TOURNAMENT_LEN),
+        )
+        .unwrap();
+
+        assert_eq!(wthor.header.created, (2023, 4, 1));
+        assert_eq!(wthor.header.year, 2023);
+        assert_eq!(wthor.header.depth, 22);
+        assert_eq!(wthor.len(), 2);
+
+        let read = wthor.games(&names).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(read[0].tournament, "Championnat du Monde");
+        assert_eq!(read[0].black, "Tastet Marc");
+        assert_eq!(read[0].white, "Caspard Hervé");
+        assert_eq!(read[1].black, "Caspard Hervé");
+        for (read, game) in read.iter().zip(&games) {
+            assert_eq!(read.game.moves(), game.moves());
+            assert_eq!(read.game.result(), game.result());
+            assert_eq!(read.score as usize, game.result().unwrap().red);
+        }
+    }
+
+    #[test]
+    fn test_unknown_names() {
+        let names = Names::default();
+
+        assert_eq!(names.player(3), "?");
+        assert_eq!(names.tournament(0), "?");
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let game = random_game(3);
+
+        assert_eq!(
+            Wthor::read(vec![20, 23]).err(),
+            Some(WthorError::Truncated(2, HEADER_LEN))
+        );
+        assert_eq!(
+            Wthor::read(header(1, 10)).err(),
+            Some(WthorError::UnsupportedSize(10))
+        );
+        assert_eq!(
+            Wthor::read(header(1, 8)).err(),
+            Some(WthorError::Truncated(HEADER_LEN, HEADER_LEN + GAME_LEN))
+        );
+
+        let mut wtb = header(2, 0);
+        let mut corrupt = game_bytes(0, 0, 0, &game);
+        corrupt[10] = 19;
+        wtb.extend(corrupt);
+        let mut illegal = game_bytes(0, 0, 0, &game);
+        illegal[8] = 11;
+        wtb.extend(illegal);
+        let wthor = Wthor::read(wtb).unwrap();
+        let games = wthor.games(&Names::default()).collect::<Vec<_>>();
+
+        assert_eq!(
+            games[0].as_ref().err(),
+            Some(&WthorError::InvalidMove(0, 3, 19))
+        );
+        assert!(matches!(
+            games[1].as_ref().err(),
+            Some(WthorError::IllegalMove(1, 1, _))
+        ));
+    }
+}
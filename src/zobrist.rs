@@ -0,0 +1,74 @@
+//! Zobrist keys: every (square, piece) pair and the side to move get a fixed
+//! pseudo-random key, and a position hashes to the XOR of the keys of its
+//! occupied squares, so placing or flipping a disc updates the hash in O(1).
+
+use crate::{coordinates::Coords, grid::Grid, piece::Piece};
+
+/// XORed in when Blue is to move.
+pub const BLUE_TO_MOVE: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Key of `piece` standing on the square at row-major `index`.
+pub fn key(index: usize, piece: Piece) -> u64 {
+    let piece = match piece {
+        Piece::Blue => 0,
+        Piece::Red => 1,
+    };
+    splitmix64((index as u64) << 1 | piece)
+}
+
+/// Key of the board size, so equal squares on boards of different sizes
+/// don't hash the same.
+pub fn size(size: usize) -> u64 {
+    splitmix64(u64::MAX - size as u64)
+}
+
+/// Key of the side to move.
+pub fn turn(piece: Piece) -> u64 {
+    match piece {
+        Piece::Blue => BLUE_TO_MOVE,
+        Piece::Red => 0,
+    }
+}
+
+/// Hashes `grid` from scratch.
+pub fn hash<G: Grid + ?Sized>(grid: &G, to_move: Piece) -> u64 {
+    let size = grid.size();
+    let start = self::size(size) ^ turn(to_move);
+    (0..size * size).fold(start, |hash, index| {
+        match grid.piece(Coords::new(index / size, index % size)) {
+            Some(piece) => hash ^ key(index, piece),
+            None => hash,
+        }
+    })
+}
+
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_keys_are_distinct() {
+        let keys = (0..256)
+            .flat_map(|i| [key(i, Piece::Blue), key(i, Piece::Red)])
+            .chain((6..=16).step_by(2).map(size))
+            .chain([BLUE_TO_MOVE])
+            .collect::<HashSet<_>>();
+
+        assert_eq!(keys.len(), 519);
+    }
+
+    #[test]
+    fn test_key_is_stable() {
+        assert_eq!(key(10, Piece::Red), key(10, Piece::Red));
+        assert_ne!(key(10, Piece::Red), key(10, Piece::Blue));
+    }
+}